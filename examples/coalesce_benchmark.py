@@ -0,0 +1,73 @@
+#!/usr/bin/env python3
+"""
+Chunk Coalescing Benchmark
+
+Feeds the same response to a parser one 2-5 byte token at a time, with and
+without coalesce_bytes, and reports total throughput alongside the worst
+per-feed latency. Coalescing trades a little responsiveness inside long text
+for fewer parse steps.
+
+    python examples/coalesce_benchmark.py
+"""
+
+import random
+import time
+from typing import List
+
+from gasp import Deserializable, Parser
+
+
+class Item(Deserializable):
+    name: str
+    description: str
+    tags: List[str]
+
+
+def make_response(items: int) -> str:
+    body = "".join(
+        f"<item><Item><name>item {i}</name>"
+        f"<description>{'lorem ipsum dolor sit amet ' * 8}</description>"
+        f"<tags><item>a</item><item>b</item></tags></Item></item>"
+        for i in range(items)
+    )
+    return f"<list>{body}</list>"
+
+
+def tokens(text: str, seed: int = 0) -> List[str]:
+    rng = random.Random(seed)
+    out, i = [], 0
+    while i < len(text):
+        size = rng.randint(2, 5)
+        out.append(text[i:i + size])
+        i += size
+    return out
+
+
+def run(chunks: List[str], coalesce_bytes: int):
+    parser = Parser(List[Item], coalesce_bytes=coalesce_bytes)
+    worst = 0.0
+    start = time.perf_counter()
+    for chunk in chunks:
+        fed = time.perf_counter()
+        parser.feed(chunk)
+        worst = max(worst, time.perf_counter() - fed)
+    result = parser.finish()
+    total = time.perf_counter() - start
+    return total, worst, len(result)
+
+
+def main():
+    text = make_response(200)
+    chunks = tokens(text)
+    print(f"{len(text)} bytes in {len(chunks)} chunks")
+    print(f"{'coalesce_bytes':>15} {'MB/s':>8} {'worst feed (ms)':>16} {'items':>6}")
+    for coalesce_bytes in (0, 16, 64, 256):
+        total, worst, items = run(chunks, coalesce_bytes)
+        print(
+            f"{coalesce_bytes:>15} {len(text) / total / 1e6:>8.2f} "
+            f"{worst * 1e3:>16.3f} {items:>6}"
+        )
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,101 @@
+//! The C ABI takes raw bytes, so chunks may end inside a multi-byte
+//! character or not be UTF-8 at all. Pushing must never panic, and valid
+//! UTF-8 must come back out byte for byte whatever the split. The
+//! declarations mirror `include/gasp_tag_finder.h`.
+
+#![no_main]
+
+mod split;
+
+use std::ffi::{c_char, c_int, c_void};
+use std::ptr;
+
+// Only linked in for its exported symbols
+use gasp as _;
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+
+const GASP_TAG_BYTES: u32 = 1;
+const GASP_OK: c_int = 0;
+
+#[repr(C)]
+struct GaspTagEvent {
+    kind: u32,
+    offset: usize,
+    depth: usize,
+    name: *const c_char,
+    name_len: usize,
+    text: *const c_char,
+    text_len: usize,
+}
+
+enum GaspTagFinder {}
+
+type Callback = Option<extern "C" fn(*mut c_void, *const GaspTagEvent)>;
+
+extern "C" {
+    fn gasp_tag_finder_new(
+        wanted: *const *const c_char,
+        wanted_len: usize,
+        ignored: *const *const c_char,
+        ignored_len: usize,
+    ) -> *mut GaspTagFinder;
+    fn gasp_tag_finder_push(
+        finder: *mut GaspTagFinder,
+        chunk: *const c_char,
+        len: usize,
+        callback: Callback,
+        user_data: *mut c_void,
+    ) -> c_int;
+    fn gasp_tag_finder_free(finder: *mut GaspTagFinder);
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    bytes: Vec<u8>,
+    cuts: Vec<u16>,
+}
+
+extern "C" fn collect(user_data: *mut c_void, event: *const GaspTagEvent) {
+    let text = unsafe { &mut *user_data.cast::<Vec<u8>>() };
+    let event = unsafe { &*event };
+    if event.kind == GASP_TAG_BYTES && !event.text.is_null() {
+        let bytes = unsafe { std::slice::from_raw_parts(event.text.cast::<u8>(), event.text_len) };
+        text.extend_from_slice(bytes);
+    }
+}
+
+/// Payload bytes reported while scanning `pieces`.
+fn payload(pieces: &[&[u8]]) -> Vec<u8> {
+    let mut text: Vec<u8> = Vec::new();
+    unsafe {
+        let finder = gasp_tag_finder_new(ptr::null(), 0, ptr::null(), 0);
+        for piece in pieces {
+            let status = gasp_tag_finder_push(
+                finder,
+                piece.as_ptr().cast(),
+                piece.len(),
+                Some(collect),
+                (&mut text as *mut Vec<u8>).cast(),
+            );
+            assert_eq!(status, GASP_OK);
+        }
+        gasp_tag_finder_free(finder);
+    }
+    text
+}
+
+fuzz_target!(|input: Input| {
+    let cuts = split::cuts(&input.cuts, input.bytes.len(), |_| true);
+    let chunked = payload(&split::pieces(
+        input.bytes.as_slice(),
+        input.bytes.len(),
+        &cuts,
+    ));
+    // Reported text is always valid UTF-8
+    let chunked = String::from_utf8(chunked).expect("payload is UTF-8");
+    if std::str::from_utf8(&input.bytes).is_ok() {
+        let whole = String::from_utf8(payload(&[&input.bytes])).unwrap();
+        assert_eq!(whole, chunked);
+    }
+});
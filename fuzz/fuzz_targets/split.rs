@@ -0,0 +1,34 @@
+//! Turning fuzzer bytes into chunk boundaries.
+
+/// Sorted, deduplicated cut points into a buffer of `len` bytes, one per
+/// 16-bit seed. `boundary` picks which offsets are allowed.
+pub fn cuts(seeds: &[u16], len: usize, boundary: impl Fn(usize) -> bool) -> Vec<usize> {
+    let mut cuts: Vec<usize> = seeds
+        .iter()
+        .map(|seed| {
+            let mut cut = *seed as usize % (len + 1);
+            while !boundary(cut) {
+                cut -= 1;
+            }
+            cut
+        })
+        .collect();
+    cuts.sort_unstable();
+    cuts.dedup();
+    cuts
+}
+
+/// `text` split at the given cut points; may include empty pieces.
+pub fn pieces<'a, T: ?Sized + std::ops::Index<std::ops::Range<usize>, Output = T>>(
+    text: &'a T,
+    len: usize,
+    cuts: &[usize],
+) -> Vec<&'a T> {
+    let mut start = 0;
+    let mut pieces = Vec::with_capacity(cuts.len() + 1);
+    for &cut in cuts.iter().chain(std::iter::once(&len)) {
+        pieces.push(&text[start..cut]);
+        start = cut;
+    }
+    pieces
+}
@@ -0,0 +1,28 @@
+//! `StreamParser::step` must return errors rather than panic, whatever the
+//! input and however it is chunked.
+
+#![no_main]
+
+mod split;
+
+use gasp::prelude::*;
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    text: String,
+    cuts: Vec<u16>,
+}
+
+fuzz_target!(|input: Input| {
+    let cuts = split::cuts(&input.cuts, input.text.len(), |cut| {
+        input.text.is_char_boundary(cut)
+    });
+    let mut parser = StreamParser::new();
+    for piece in split::pieces(input.text.as_str(), input.text.len(), &cuts) {
+        if parser.step(piece).is_err() {
+            break;
+        }
+    }
+});
@@ -0,0 +1,80 @@
+//! `TagFinder::push` must not panic on any input split at any character
+//! boundaries, and must report the same tags, at the same offsets, with the
+//! same text in between, as when the whole input arrives at once.
+
+#![no_main]
+
+mod split;
+
+use gasp::prelude::*;
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    text: String,
+    cuts: Vec<u16>,
+    wanted: Vec<String>,
+    ignored: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Seen {
+    Open(usize, String, usize),
+    Text(usize, String),
+    Close(usize, String, usize),
+}
+
+/// Events from feeding `pieces` in order, with adjacent text merged so that
+/// different chunkings can be compared.
+fn scan(input: &Input, pieces: &[&str]) -> Vec<Seen> {
+    let mut finder = TagFinder::new_with_filter(input.wanted.clone(), input.ignored.clone());
+    let mut seen = Vec::new();
+    for piece in pieces {
+        finder
+            .push(piece, |offset, event| {
+                match event {
+                    TagEvent::Open(tag) => seen.push(Seen::Open(offset, tag.name, tag.depth)),
+                    TagEvent::Bytes(text) => match seen.last_mut() {
+                        Some(Seen::Text(start, prev)) if *start + prev.len() == offset => {
+                            prev.push_str(&text)
+                        }
+                        _ => seen.push(Seen::Text(offset, text)),
+                    },
+                    TagEvent::Close(name, depth) => seen.push(Seen::Close(offset, name, depth)),
+                }
+                Ok(())
+            })
+            .unwrap();
+    }
+    finder.finish();
+    seen
+}
+
+fuzz_target!(|input: Input| {
+    let cuts = split::cuts(&input.cuts, input.text.len(), |cut| {
+        input.text.is_char_boundary(cut)
+    });
+    let whole = scan(&input, &[input.text.as_str()]);
+    let chunked = scan(
+        &input,
+        &split::pieces(input.text.as_str(), input.text.len(), &cuts),
+    );
+    assert_eq!(whole, chunked, "cuts at {:?}", cuts);
+
+    // Opens and closes nest properly
+    let mut depth = 0;
+    for event in &whole {
+        match event {
+            Seen::Open(_, _, d) => {
+                depth += 1;
+                assert_eq!(*d, depth);
+            }
+            Seen::Close(_, _, d) => {
+                assert_eq!(*d, depth);
+                depth -= 1;
+            }
+            Seen::Text(..) => {}
+        }
+    }
+});
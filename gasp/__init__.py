@@ -8,21 +8,46 @@ data extraction actually pleasant.
 
 from . import template_helpers
 from . import jinja_helpers
+from . import stream_helpers
+from .constraints import Max, MaxLength, Min, MinLength, Pattern
 from .deserializable import Deserializable
+from .paths import Change, SnapshotDiffer, diff_results, get_path, pointer, set_path, set_pointer
 
 # Import native components from the Rust module
-from .gasp import Parser, StreamParser
+from .gasp import Parser, StreamParser, capabilities
 
 # Import key Jinja helpers for convenience
 from .jinja_helpers import render_template, render_file_template
+from .stream_helpers import ChannelSink, SnapshotWatch, SseAdapter, merge_results, parse_async_stream, parse_stream, truncate_safe
 
 __version__ = "1.0.0"
 __all__ = [
     "Parser", 
     "StreamParser", 
+    "capabilities",
     "Deserializable", 
+    "MaxLength",
+    "MinLength",
+    "Pattern",
+    "Min",
+    "Max",
     "template_helpers", 
     "jinja_helpers",
+    "stream_helpers",
     "render_template",
-    "render_file_template"
+    "render_file_template",
+    "parse_async_stream",
+    "parse_stream",
+    "SseAdapter",
+    "truncate_safe",
+    "ChannelSink",
+    "SnapshotWatch",
+    "merge_results",
+    "get_path",
+    "pointer",
+    "set_path",
+    "set_pointer",
+    "Change",
+    "diff_results",
+    "SnapshotDiffer"
 ]
@@ -0,0 +1,104 @@
+"""
+Field constraints attached with typing.Annotated.
+
+These are read both by the parser, which enforces them as each field
+closes, and by the template helpers, which describe them in the prompt.
+"""
+
+
+class MaxLength:
+    """
+    Cap a string field at max_length characters.
+
+    on_overflow decides what happens to a longer string:
+    - "error": raise ValueError (see Parser's on_error to skip the element)
+    - "truncate": keep the first max_length characters and record the path
+      in Parser.truncated_paths()
+
+    Example usage:
+    class Tweet(Deserializable):
+        text: Annotated[str, MaxLength(280, on_overflow="truncate")]
+    """
+
+    ON_OVERFLOW_POLICIES = ("error", "truncate")
+
+    def __init__(self, max_length: int, on_overflow: str = "error"):
+        if max_length < 0:
+            raise ValueError(f"max_length must be >= 0, got {max_length!r}")
+        if on_overflow not in self.ON_OVERFLOW_POLICIES:
+            raise ValueError(f"Unknown on_overflow policy {on_overflow!r}; expected one of {self.ON_OVERFLOW_POLICIES}")
+        self.max_length = max_length
+        self.on_overflow = on_overflow
+
+    def __repr__(self) -> str:
+        return f"MaxLength({self.max_length!r}, on_overflow={self.on_overflow!r})"
+
+
+class MinLength:
+    """
+    Require a string field to have at least min_length characters.
+
+    Example usage:
+    class Country(Deserializable):
+        name: Annotated[str, MinLength(2)]
+    """
+
+    def __init__(self, min_length: int):
+        if min_length < 0:
+            raise ValueError(f"min_length must be >= 0, got {min_length!r}")
+        self.min_length = min_length
+
+    def __repr__(self) -> str:
+        return f"MinLength({self.min_length!r})"
+
+
+class Pattern:
+    """
+    Require a string field to match a regular expression.
+
+    The match is a search, so anchor the pattern with ^ and $ to match the
+    whole string. The parser uses Rust regex syntax, which has no
+    lookaround or backreferences.
+
+    Example usage:
+    class Flight(Deserializable):
+        airport: Annotated[str, Pattern("^[A-Z]{3}$")]
+    """
+
+    def __init__(self, pattern: str):
+        self.pattern = pattern
+
+    def __repr__(self) -> str:
+        return f"Pattern({self.pattern!r})"
+
+
+class Min:
+    """
+    Require a number field to be at least min (inclusive).
+
+    Example usage:
+    class Score(Deserializable):
+        points: Annotated[int, Min(0), Max(100)]
+    """
+
+    def __init__(self, min: float):
+        self.ge = min
+
+    def __repr__(self) -> str:
+        return f"Min({self.ge!r})"
+
+
+class Max:
+    """
+    Require a number field to be at most max (inclusive).
+
+    Example usage:
+    class Score(Deserializable):
+        points: Annotated[int, Min(0), Max(100)]
+    """
+
+    def __init__(self, max: float):
+        self.le = max
+
+    def __repr__(self) -> str:
+        return f"Max({self.le!r})"
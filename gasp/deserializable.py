@@ -12,6 +12,8 @@ class Deserializable:
 
         # Initialize all annotated fields with appropriate defaults
         for field_name, field_type in annotations.items():
+            # Annotated[T, ...] defaults like T
+            field_type = getattr(field_type, "__origin__", field_type) if hasattr(field_type, "__metadata__") else field_type
             if field_name not in kwargs:
                 # Check if the class has a default value for this field
                 if hasattr(self.__class__, field_name):
@@ -1,4 +1,4 @@
-from typing import Optional, Any, Type, Dict, List, TypeVar, Generic, Union, ClassVar
+from typing import NamedTuple, Optional, Any, Type, Dict, List, Tuple, TypeVar, Generic, Union, ClassVar, AsyncIterable, AsyncIterator, Callable, Iterable, Iterator
 import jinja2
 
 T = TypeVar('T')
@@ -40,25 +40,55 @@ class Deserializable:
 class Parser(Generic[T]):
     """Parser for incrementally building typed objects from JSON streams"""
     
-    def __init__(self, type_obj: Optional[Any] = None, ignored_tags: Optional[List[str]] = None) -> None:
+    def __init__(self, type_obj: Optional[Any] = None, *, ignored_tags: Optional[List[str]] = None, metadata_tags: Optional[List[str]] = None, eager_complete: bool = False, multiple: bool = False, string_deltas: bool = False, decimal_floats: bool = False, passthrough_text: bool = False, empty_as: Optional[Dict[str, str]] = None, duplicate_keys: str = "last_wins", max_depth: Optional[int] = None, max_string_length: Optional[int] = None, max_nodes: Optional[int] = None, max_buffer_bytes: Optional[int] = None, trace_tags: bool = False, on_error: str = "raise", python_numbers: bool = False, python_literals: bool = False, coalesce_bytes: int = 0, strict_types: bool = False) -> None:
         """
         Initialize a parser for the given type.
         
         Args:
-            type_obj: The Python type to parse into
+            type_obj: The Python type to parse into. Without one, only ignored_tags, metadata_tags,
+                passthrough_text, the max_* limits, trace_tags and coalesce_bytes may be given;
+                other options raise ValueError
             ignored_tags: List of tag names to ignore. Defaults to ["think", "thinking", "system"]
+            metadata_tags: Tags whose text is captured separately from the main payload
+            eager_complete: Complete a class result once all its fields have closed, without waiting for the root close tag
+            multiple: Keep parsing after each root value and collect them all (see get_results)
+            string_deltas: Record the text appended to each field as it streams (see take_deltas)
+            decimal_floats: Parse float fields as decimal.Decimal to keep their exact digits
+            passthrough_text: Keep the prose outside the payload tags (see take_text)
+            empty_as: Per-tag policy ("*" for all) for tags that close without content:
+                "null", "empty_object", "empty_string" or "error" (see empty_paths)
+            duplicate_keys: How a repeated object field or dict key is resolved:
+                "last_wins", "first_wins", "error" or "collect" (keep every value in a list)
+            max_depth: Raise ValueError when more tags than this are open at once
+            max_string_length: Raise ValueError when a field's text grows past this many bytes
+            max_nodes: Raise ValueError when the stream opens more tags than this
+            max_buffer_bytes: Raise ValueError when buffered_bytes() grows past this
+            trace_tags: Record every tag event and skipped tag with its byte offset
+            python_numbers: Also accept Python numeric literals (0x1F, 0o17, 0b101, 1_000_000) in number fields
+            python_literals: Read a field of None as None, and (1, 2)-style text in list, set and tuple tags
+            coalesce_bytes: Hold chunks until this many bytes are pending or a chunk contains ">", then parse them together
+            strict_types: Raise ValueError as soon as a number or bool field's text can't be its type, instead of reading None or False
+            on_error: What a failing list, set, tuple or dict element does: "raise", "skip" (drop it) or "null" (replace it with None)
         """
         pass
     
     @staticmethod
-    def from_pydantic(pydantic_model: Any) -> 'Parser':
-        """Create a parser for a Pydantic model"""
+    def from_pydantic(pydantic_model: Any, *, ignored_tags: Optional[List[str]] = None, metadata_tags: Optional[List[str]] = None, eager_complete: bool = False, multiple: bool = False, string_deltas: bool = False, decimal_floats: bool = False, passthrough_text: bool = False, empty_as: Optional[Dict[str, str]] = None, duplicate_keys: str = "last_wins", max_depth: Optional[int] = None, max_string_length: Optional[int] = None, max_nodes: Optional[int] = None, max_buffer_bytes: Optional[int] = None, trace_tags: bool = False, on_error: str = "raise", python_numbers: bool = False, python_literals: bool = False, coalesce_bytes: int = 0, strict_types: bool = False) -> 'Parser':
+        """Create a parser for a Pydantic model; options are as for Parser, but no tags are ignored by default"""
         pass
     
     def feed(self, chunk: str) -> Optional[T]:
         """Feed a chunk of XML data and return a partial object if available"""
         pass
     
+    def feed_bytes(self, chunk: bytes) -> Optional[T]:
+        """Feed raw UTF-8 bytes; characters split across chunks are reassembled"""
+        pass
+    
+    def feed_tagged(self, chunk: str) -> Optional[Tuple[str, T, bool]]:
+        """Feed a chunk and return (tag_name, value, complete) for the current value, if any"""
+        pass
+    
     def is_complete(self) -> bool:
         """Check if parsing is complete"""
         pass
@@ -67,19 +97,100 @@ class Parser(Generic[T]):
         """Get the current partial object without validation"""
         pass
     
+    def finish(self, strict: bool = False) -> Optional[T]:
+        """Signal the end of the stream, closing any open tags, and return the final object.
+        With strict=True, raise ValueError naming every value left open instead."""
+        pass
+    
+    def unclosed_tags(self) -> List[str]:
+        """Tags that were still open when finish() was called, outermost first"""
+        pass
+    
+    def unclosed_paths(self) -> List[str]:
+        """Paths of the values still open when finish() was called, e.g. Person.hobbies[1]"""
+        pass
+    
+    def truncated_paths(self) -> List[Tuple[str, int]]:
+        """(path, original length) for every string cut down to its MaxLength(..., on_overflow="truncate")"""
+        pass
+    
+    def issues(self) -> List[Tuple[str, int, str]]:
+        """(path, offset, message) for every element error recovered from under on_error"""
+        pass
+    
+    def empty_paths(self) -> List[Tuple[str, str]]:
+        """(path, policy) for every empty tag replaced by an empty_as policy"""
+        pass
+    
+    def tag_trace(self) -> str:
+        """Recorded tag events as a JSON array of {"offset", "kind", ...} objects (requires trace_tags; absent from builds without serde_json)"""
+        pass
+    
+    def format_tag_trace(self) -> str:
+        """Recorded tag events, one per line with offsets, indented by depth (requires trace_tags)"""
+        pass
+    
+    def stats(self) -> Dict[str, int]:
+        """Lifetime counters: bytes fed, events handled, snapshots built and peak_depth (most frames open at once)"""
+        pass
+    
+    def reset(self) -> None:
+        """Start over for a new stream, keeping the type, options and subscriptions"""
+        pass
+
+    def buffered_bytes(self) -> int:
+        """Bytes of input held but not yet part of a value (split tags, partial UTF-8, open field text)"""
+        pass
+    
+    def get_results(self) -> List[Tuple[str, T]]:
+        """Values completed so far as (tag_name, value) pairs when created with multiple=True"""
+        pass
+    
+    def subscribe(self, path: str, callback: Callable[[str, Any], None]) -> None:
+        """Call callback(path, value) whenever the value at path (e.g. "Answer.steps[0]") completes"""
+        pass
+    
+    def take_text(self) -> str:
+        """Prose outside the payload tags since the last call, when created with passthrough_text=True"""
+        pass
+    
+    def take_deltas(self) -> List[Tuple[str, str]]:
+        """Text appended to fields since the last call, as (path, appended) pairs"""
+        pass
+    
+    def get_metrics(self) -> Dict[str, float]:
+        """Rolling chunks/bytes/events per second, average step latency and totals (requires the `metrics` feature)"""
+        pass
+    
+    def set_metrics_recorder(self, recorder: Callable[[int, int, float], None]) -> None:
+        """Also report every chunk to recorder(bytes, events, latency_secs) (requires the `metrics` feature)"""
+        pass
+    
+    def set_clock(self, clock: Callable[[], float]) -> None:
+        """Time steps with clock(), returning monotonic seconds, instead of the system clock (requires the `metrics` feature)"""
+        pass
+    
+    def get_metadata(self) -> Dict[str, str]:
+        """Get the text of the metadata tags closed so far, keyed by tag name"""
+        pass
+    
     def validate(self) -> Optional[T]:
         """Perform full validation on the completed object"""
         pass
 
 class StreamParser:
-    """Low-level streaming JSON parser"""
+    """Low-level streaming XML parser with no type conversion"""
     
     def __init__(self) -> None:
         """Initialize a streaming parser"""
         pass
     
     def parse(self, chunk: str) -> Optional[Any]:
-        """Feed a chunk of JSON data and return parsed value if complete"""
+        """Feed a chunk of XML and return the root element once it closes.
+
+        Elements come back as ``{"name": ..., "attributes": {...},
+        "children": [...]}`` and text as ``str``.
+        """
         pass
     
     def is_done(self) -> bool:
@@ -100,15 +211,18 @@ def type_to_format_instructions(type_obj: Type, name: Optional[str] = None) -> s
     """
     pass
 
-def interpolate_prompt(template: str, type_obj: Type, format_tag: str = "return_type", name: Optional[str] = None) -> str:
+def interpolate_prompt(template: str, type_obj: Type, format_tag: str = "return_type", name: Optional[str] = None, strict: bool = False) -> str:
     """
     Replace {{format_tag}} in the template with format instructions for the type.
+    Whitespace just inside the braces is ignored ({{ return_type }} matches too).
+    A placeholder written as \\{{...}} is kept literally, minus the backslash.
     
     Args:
         template: The prompt template with {{format_tag}} placeholders
         type_obj: The Python type to generate instructions for
         format_tag: The tag to replace (default: "return_type")
         name: Optional name to use for the type tag (defaults to class name)
+        strict: Raise ValueError, with its offset, for any other placeholder
         
     Returns:
         The interpolated prompt
@@ -116,10 +230,14 @@ def interpolate_prompt(template: str, type_obj: Type, format_tag: str = "return_
     pass
 
 # Jinja2 helper functions
-def create_type_environment() -> jinja2.Environment:
+def create_type_environment(strict: bool = False, fragments: Optional[Dict[str, str]] = None) -> jinja2.Environment:
     """
     Create a Jinja2 environment with GASP type formatting filters.
     
+    Args:
+        strict: Raise jinja2.UndefinedError for variables missing from the context
+        fragments: Named templates for {% include %} and {% extends %}
+    
     Returns:
         A Jinja2 Environment with GASP custom filters.
     """
@@ -150,7 +268,7 @@ def type_description_filter(type_obj: Type) -> str:
     """
     pass
 
-def render_template(template_str: str, context: Dict[str, Any], env: Optional[jinja2.Environment] = None) -> str:
+def render_template(template_str: str, context: Dict[str, Any], env: Optional[jinja2.Environment] = None, strict: bool = False, fragments: Optional[Dict[str, str]] = None) -> str:
     """
     Render a Jinja2 template with the given context.
     
@@ -158,13 +276,15 @@ def render_template(template_str: str, context: Dict[str, Any], env: Optional[ji
         template_str: Jinja2 template string
         context: Dictionary of variables to use in the template
         env: Optional Jinja2 environment (creates one with GASP filters if not provided)
+        strict: Raise jinja2.UndefinedError for variables missing from the context
+        fragments: Named templates for {% include %} and {% extends %}
         
     Returns:
         The rendered template as a string
     """
     pass
 
-def render_file_template(template_path: str, context: Dict[str, Any], env: Optional[jinja2.Environment] = None) -> str:
+def render_file_template(template_path: str, context: Dict[str, Any], env: Optional[jinja2.Environment] = None, strict: bool = False, fragments: Optional[Dict[str, str]] = None) -> str:
     """
     Render a Jinja2 template file with the given context.
     
@@ -172,12 +292,162 @@ def render_file_template(template_path: str, context: Dict[str, Any], env: Optio
         template_path: Path to the Jinja2 template file
         context: Dictionary of variables to use in the template
         env: Optional Jinja2 environment (creates one with GASP filters if not provided)
+        strict: Raise jinja2.UndefinedError for variables missing from the context
+        fragments: Named templates for {% include %} and {% extends %}
         
     Returns:
         The rendered template as a string
     """
     pass
 
+def capabilities() -> Dict[str, Any]:
+    """
+    Describe this build of the native module.
+    
+    Returns:
+        {"version": str, "formats": ["xml"],
+         "features": {"ffi": bool, "intern": bool, "metrics": bool, "serde": bool, "serde_json": bool, "tracing": bool, "unsafe_debug": bool},
+         "limits": default Parser limits (None means unlimited)}
+    """
+    pass
+
+# Stream helper functions
+def get_path(value: Any, path: str, default: Any = ...) -> Any:
+    """
+    Value at a dotted path such as "items[2].name", optionally led by the
+    root type name ("Batch.items[2].name"). Raises KeyError if missing and
+    no default is given.
+    """
+    pass
+
+def pointer(value: Any, pointer: str, default: Any = ...) -> Any:
+    """Value at an RFC 6901 JSON pointer such as "/items/2/name" """
+    pass
+
+def set_path(value: Any, path: str, new: Any) -> None:
+    """Replace the value at a dotted path in place"""
+    pass
+
+def set_pointer(value: Any, pointer: str, new: Any) -> None:
+    """Replace the value at an RFC 6901 JSON pointer in place"""
+    pass
+
+class Change(NamedTuple):
+    """One difference between two results: kind is "added", "removed" or "modified" """
+    kind: str
+    path: str
+    old: Any
+    new: Any
+
+def diff_results(old: Any, new: Any) -> List[Change]:
+    """Changes from old to new, with the dotted paths get_path accepts"""
+    pass
+
+class SnapshotDiffer:
+    """Changes between successive snapshots of one stream; copies each snapshot, since the parser updates it in place"""
+    def __init__(self) -> None: ...
+    def update(self, snapshot: Any) -> List[Change]: ...
+
+def parse_stream(parser: Parser[T], chunks: Iterable[str]) -> Iterator[T]:
+    """
+    Feed chunks from an iterable into a parser.
+    
+    Args:
+        parser: The gasp Parser to feed
+        chunks: Iterable of text chunks, e.g. from a blocking LLM client
+        
+    Yields:
+        The partial (and finally complete) parsed object after each chunk
+    """
+    pass
+
+def parse_async_stream(parser: Parser[T], chunks: AsyncIterable[str]) -> AsyncIterator[T]:
+    """
+    Feed chunks from an async iterable into a parser.
+    
+    Args:
+        parser: The gasp Parser to feed
+        chunks: Async iterable of text chunks, e.g. from an LLM client
+        
+    Yields:
+        The partial (and finally complete) parsed object after each chunk
+    """
+    pass
+
+def truncate_safe(text: str, max_chars: int, ellipsis: str = "\u2026") -> str:
+    """
+    Shorten text to at most max_chars (ellipsis included) without splitting
+    combining sequences, emoji ZWJ sequences or surrogate pairs.
+    """
+    pass
+
+class ChannelSink(Generic[T]):
+    """
+    Push a parser's snapshots into a bounded queue.Queue, with overflow
+    "block", "drop_oldest" or "coalesce_latest".
+    """
+    parser: Parser[T]
+    channel: Any
+    overflow: str
+
+    def __init__(self, parser: Parser[T], channel: Any, overflow: str = "block") -> None: ...
+
+    def feed(self, chunk: str) -> Optional[T]:
+        """Feed a chunk and push the resulting snapshot, if any"""
+        pass
+
+    def push(self, item: T) -> None:
+        """Put item on the channel according to the overflow policy"""
+        pass
+
+class SnapshotWatch(Generic[T]):
+    """
+    Share a parser's latest snapshot with other threads. Readers poll
+    latest() or block in wait(); both return (version, snapshot, done).
+    """
+    parser: Parser[T]
+
+    def __init__(self, parser: Parser[T]) -> None: ...
+
+    def feed(self, chunk: str) -> Optional[T]:
+        """Feed a chunk and publish the resulting snapshot, if any"""
+        pass
+
+    def finish(self) -> Optional[T]:
+        """Finish the parser, publish its final value and wake every reader"""
+        pass
+
+    def publish(self, value: Optional[T], done: bool = False) -> None: ...
+
+    def latest(self) -> Tuple[int, Optional[T], bool]: ...
+
+    def wait(self, after: int = 0, timeout: Optional[float] = None) -> Tuple[int, Optional[T], bool]:
+        """Block until a snapshot newer than version after, or the end of the stream"""
+        pass
+
+class SseAdapter(Generic[T]):
+    """
+    Feed a parser from a Server-Sent Events response body, extracting the text
+    of OpenAI and Anthropic streaming events.
+    """
+    parser: Parser[T]
+    done: bool
+
+    def __init__(self, parser: Parser[T]) -> None: ...
+
+    def feed(self, data: Union[bytes, str]) -> Optional[T]:
+        """
+        Feed the next chunk of the response body.
+        
+        Args:
+            data: Raw bytes (or text) of the event stream, split anywhere
+            
+        Returns:
+            The parser's current object, or None if no text was fed
+        """
+        pass
+
 # Module exports
 template_helpers: Any
 jinja_helpers: Any
+stream_helpers: Any
@@ -6,15 +6,23 @@ It includes custom filters and functions to generate type-aware prompts with mor
 advanced templating capabilities than the basic interpolate_prompt function.
 """
 import inspect
+import os
 from typing import Any, Dict, Optional, Type, Union
 import jinja2
 
 from .template_helpers import type_to_format_instructions
 
-def create_type_environment() -> jinja2.Environment:
+def create_type_environment(strict: bool = False,
+                            fragments: Optional[Dict[str, str]] = None) -> jinja2.Environment:
     """
     Create a Jinja2 environment with GASP type formatting filters.
     
+    Args:
+        strict: Raise jinja2.UndefinedError when a template uses a variable
+            missing from the context, instead of rendering it as empty
+        fragments: Named template sources that templates can pull in with
+            {% include "name" %} or build on with {% extends "name" %}
+    
     Returns:
         A Jinja2 Environment with GASP custom filters.
     """
@@ -22,6 +30,8 @@ def create_type_environment() -> jinja2.Environment:
         trim_blocks=True,
         lstrip_blocks=True,
         keep_trailing_newline=True,
+        undefined=jinja2.StrictUndefined if strict else jinja2.Undefined,
+        loader=jinja2.DictLoader(fragments or {}),
     )
     
     # Add custom filters for type formatting
@@ -73,7 +83,8 @@ def type_description_filter(type_obj: Type) -> str:
     return type_name
 
 def render_template(template_str: str, context: Dict[str, Any], 
-                   env: Optional[jinja2.Environment] = None) -> str:
+                   env: Optional[jinja2.Environment] = None, strict: bool = False,
+                   fragments: Optional[Dict[str, str]] = None) -> str:
     """
     Render a Jinja2 template with the given context.
     
@@ -94,40 +105,60 @@ def render_template(template_str: str, context: Dict[str, Any],
     
     prompt = render_template(template, context)
     ```
+
+    Shared pieces can be passed as fragments:
+    ```python
+    fragments = {"preamble": "You are a careful assistant."}
+    render_template('{% include "preamble" %} Generate a person.', {}, fragments=fragments)
+    ```
     
     Args:
         template_str: Jinja2 template string
         context: Dictionary of variables to use in the template
         env: Optional Jinja2 environment (creates one with GASP filters if not provided)
+        strict: Raise jinja2.UndefinedError for variables missing from the context
+            (only used when env is not provided)
+        fragments: Named templates for {% include %} and {% extends %}
+            (only used when env is not provided)
         
     Returns:
         The rendered template as a string
     """
     if env is None:
-        env = create_type_environment()
+        env = create_type_environment(strict, fragments)
     
     template = env.from_string(template_str)
     return template.render(**context)
 
 def render_file_template(template_path: str, context: Dict[str, Any],
-                        env: Optional[jinja2.Environment] = None) -> str:
+                        env: Optional[jinja2.Environment] = None, strict: bool = False,
+                        fragments: Optional[Dict[str, str]] = None) -> str:
     """
     Render a Jinja2 template file with the given context.
+
+    {% include %} and {% extends %} look for files next to the template,
+    then relative to the current directory, then in fragments.
     
     Args:
         template_path: Path to the Jinja2 template file
         context: Dictionary of variables to use in the template
         env: Optional Jinja2 environment (creates one with GASP filters if not provided)
+        strict: Raise jinja2.UndefinedError for variables missing from the context
+            (only used when env is not provided)
+        fragments: Named templates for {% include %} and {% extends %}
         
     Returns:
         The rendered template as a string
     """
     if env is None:
-        env = create_type_environment()
+        env = create_type_environment(strict)
         
     # Configure the file system loader
-    file_loader = jinja2.FileSystemLoader(searchpath='./')
-    env.loader = file_loader
+    search_path = [os.path.dirname(template_path) or './', './']
+    loaders = [jinja2.FileSystemLoader(searchpath=search_path)]
+    if fragments:
+        loaders.append(jinja2.DictLoader(fragments))
+    env.loader = jinja2.ChoiceLoader(loaders) if len(loaders) > 1 else loaders[0]
     
-    template = env.get_template(template_path)
+    template = env.get_template(os.path.basename(template_path))
     return template.render(**context)
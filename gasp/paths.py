@@ -0,0 +1,244 @@
+"""
+Look up and replace values inside parsed results by path.
+
+Two notations are accepted:
+- dotted paths, as used in merge_results reports and Parser issues:
+  "address.city", "items[2].name". The root type's name may lead the
+  path ("Person.address.city"), so reported paths can be fed straight back.
+- RFC 6901 JSON pointers: "/address/city", "/items/2/name", with "~1"
+  for "/" and "~0" for "~" inside a key.
+
+Objects are traversed by attribute, mappings by key, and lists and tuples
+by index. diff_results reports changes between two results with the same
+dotted paths.
+"""
+
+import copy
+import re
+from typing import Any, Dict, List, Mapping, MutableMapping, NamedTuple, Optional, Sequence, Union
+
+_MISSING = object()
+
+Step = Union[str, int]
+
+_PLAIN_KEY = re.compile(r"[^.\[\]'\"]+")
+_SEGMENT = re.compile(r"([^.\[\]]+)|\[(-?\d+)\]|\[['\"]([^'\"]*)['\"]\]")
+
+
+def parse_path(path: str) -> List[Step]:
+    """Split a dotted path into keys and integer indices."""
+    steps: List[Step] = []
+    position = 0
+    while position < len(path):
+        if path[position] == "." and steps:
+            position += 1
+        match = _SEGMENT.match(path, position)
+        if match is None:
+            raise ValueError(f"Invalid path {path!r} at offset {position}")
+        name, index, quoted = match.groups()
+        if index is not None:
+            steps.append(int(index))
+        else:
+            steps.append(quoted if quoted is not None else name)
+        position = match.end()
+    return steps
+
+
+def parse_pointer(pointer: str) -> List[Step]:
+    """Split an RFC 6901 pointer into unescaped reference tokens."""
+    if pointer == "":
+        return []
+    if not pointer.startswith("/"):
+        raise ValueError(f"Invalid JSON pointer {pointer!r}: must be empty or start with '/'")
+    return [token.replace("~1", "/").replace("~0", "~") for token in pointer[1:].split("/")]
+
+
+def _child(value: Any, step: Step) -> Any:
+    if isinstance(value, Mapping):
+        return value[step]
+    if isinstance(value, Sequence) and not isinstance(value, str):
+        return value[_index(step)]
+    if isinstance(step, str) and not step.startswith("__") and hasattr(value, step):
+        return getattr(value, step)
+    raise KeyError(step)
+
+
+def _index(step: Step) -> int:
+    if isinstance(step, int):
+        return step
+    if not step.isdigit():
+        raise KeyError(step)
+    return int(step)
+
+
+def _walk(value: Any, steps: List[Step], path: str) -> Any:
+    for depth, step in enumerate(steps):
+        try:
+            value = _child(value, step)
+        except (KeyError, IndexError, TypeError):
+            raise KeyError(f"{path!r}: nothing at {_describe(steps[: depth + 1])}") from None
+    return value
+
+
+def _describe(steps: List[Step]) -> str:
+    """The dotted path for steps; "" for the value itself."""
+    out = ""
+    for step in steps:
+        if isinstance(step, int):
+            out += f"[{step}]"
+        elif _PLAIN_KEY.fullmatch(step):
+            out += f".{step}" if out else step
+        else:
+            out += f'["{step}"]'
+    return out
+
+
+def _relative(value: Any, steps: List[Step]) -> List[Step]:
+    """Drop a leading root type name ("Person.email") the value doesn't have as a field."""
+    if steps and isinstance(steps[0], str) and steps[0] == type(value).__name__:
+        try:
+            _child(value, steps[0])
+        except (KeyError, IndexError, TypeError):
+            return steps[1:]
+    return steps
+
+
+def get_path(value: Any, path: str, default: Any = _MISSING) -> Any:
+    """
+    Return the value at a dotted path.
+
+    Example usage:
+    get_path(person, "address.city")
+    get_path(batch, "Batch.items[3].price", default=None)
+
+    Raises:
+        KeyError: if nothing is at the path and no default was given
+    """
+    try:
+        return _walk(value, _relative(value, parse_path(path)), path)
+    except KeyError:
+        if default is _MISSING:
+            raise
+        return default
+
+
+def pointer(value: Any, pointer: str, default: Any = _MISSING) -> Any:
+    """Return the value at an RFC 6901 JSON pointer, e.g. "/items/0/name"."""
+    try:
+        return _walk(value, parse_pointer(pointer), pointer)
+    except KeyError:
+        if default is _MISSING:
+            raise
+        return default
+
+
+def _assign(value: Any, steps: List[Step], new: Any, path: str) -> None:
+    if not steps:
+        raise ValueError(f"{path!r} refers to the value itself; there is nothing to assign to")
+    parent = _walk(value, steps[:-1], path)
+    last = steps[-1]
+    if isinstance(parent, MutableMapping):
+        parent[last] = new
+    elif isinstance(parent, list):
+        try:
+            parent[_index(last)] = new
+        except (KeyError, IndexError):
+            raise KeyError(f"{path!r}: nothing at {_describe(steps)}") from None
+    elif isinstance(last, str) and not isinstance(parent, (str, tuple)):
+        setattr(parent, last, new)
+    else:
+        raise TypeError(f"{path!r}: {type(parent).__name__} at {_describe(steps[:-1]) or 'the root'} can't be modified")
+
+
+def set_path(value: Any, path: str, new: Any) -> None:
+    """Replace the value at a dotted path in place. The parent must exist."""
+    _assign(value, _relative(value, parse_path(path)), new, path)
+
+
+def set_pointer(value: Any, pointer: str, new: Any) -> None:
+    """Replace the value at an RFC 6901 JSON pointer in place. The parent must exist."""
+    _assign(value, parse_pointer(pointer), new, pointer)
+
+
+class Change(NamedTuple):
+    """One difference between two results: kind is "added", "removed" or "modified"."""
+
+    kind: str
+    path: str
+    old: Any
+    new: Any
+
+
+def _fields(value: Any) -> Optional[Dict[str, Any]]:
+    if isinstance(value, Mapping):
+        return dict(value)
+    if hasattr(value, "__dict__") and not isinstance(value, type):
+        return {key: item for key, item in vars(value).items() if not key.startswith("_")}
+    return None
+
+
+def _diff(old: Any, new: Any, steps: List[Step], changes: List[Change]) -> None:
+    if old is new:
+        return
+    if isinstance(old, list) and isinstance(new, list):
+        for index in range(max(len(old), len(new))):
+            if index >= len(old):
+                changes.append(Change("added", _describe(steps + [index]), None, new[index]))
+            elif index >= len(new):
+                changes.append(Change("removed", _describe(steps + [index]), old[index], None))
+            else:
+                _diff(old[index], new[index], steps + [index], changes)
+        return
+    old_fields, new_fields = _fields(old), _fields(new)
+    if old_fields is not None and new_fields is not None and type(old) is type(new):
+        for key in list(old_fields) + [key for key in new_fields if key not in old_fields]:
+            if key not in new_fields:
+                changes.append(Change("removed", _describe(steps + [key]), old_fields[key], None))
+            elif key not in old_fields:
+                changes.append(Change("added", _describe(steps + [key]), None, new_fields[key]))
+            else:
+                _diff(old_fields[key], new_fields[key], steps + [key], changes)
+        return
+    if type(old) is not type(new) or old != new:
+        changes.append(Change("modified", _describe(steps), old, new))
+
+
+def diff_results(old: Any, new: Any) -> List[Change]:
+    """
+    List what changed between two results, in field order, with the paths
+    get_path accepts. Fields, keys and list items only one side has are
+    "added" or "removed"; anything else that differs is "modified" at the
+    deepest path where the two still have the same shape.
+
+    The parser updates its result in place, so keep a copy of the older
+    snapshot (or use SnapshotDiffer) rather than comparing a snapshot with
+    itself.
+
+    Example usage:
+    diff_results(before, after)
+    # [Change("modified", "name", "Ad", "Ada"), Change("added", "tags[0]", None, "x")]
+    """
+    changes: List[Change] = []
+    _diff(old, new, [], changes)
+    return changes
+
+
+class SnapshotDiffer:
+    """
+    Turn successive snapshots of one stream into changes since the last one.
+
+    Example usage:
+    differ = SnapshotDiffer()
+    for chunk in llm_chunks:
+        for change in differ.update(parser.feed(chunk)):
+            apply_to_ui(change)
+    """
+
+    def __init__(self) -> None:
+        self._last: Any = None
+
+    def update(self, snapshot: Any) -> List[Change]:
+        """Changes from the previous snapshot (None before the first) to this one."""
+        changes = diff_results(self._last, snapshot)
+        self._last = copy.deepcopy(snapshot)
+        return changes
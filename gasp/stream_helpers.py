@@ -0,0 +1,440 @@
+"""
+Helpers for driving a GASP Parser from a stream of LLM output chunks.
+
+These wrap the feed() loop so parsers can be plugged directly into
+streaming LLM clients.
+"""
+import codecs
+import copy
+import decimal
+import json
+import queue
+import threading
+import typing
+import unicodedata
+from typing import Any, AsyncIterable, AsyncIterator, Dict, Iterable, Iterator, List, Optional, Tuple, Union
+
+
+_ZWJ = "\u200d"
+
+
+def _attaches_to_previous(ch: str) -> bool:
+    """True for characters that render as part of the character before them."""
+    code = ord(ch)
+    return (
+        unicodedata.category(ch) in ("Mn", "Mc", "Me")  # combining marks
+        or ch == _ZWJ
+        or 0xFE00 <= code <= 0xFE0F  # variation selectors
+        or 0xE0100 <= code <= 0xE01EF
+        or 0x1F3FB <= code <= 0x1F3FF  # emoji skin tone modifiers
+        or 0xDC00 <= code <= 0xDFFF  # low half of a surrogate pair
+    )
+
+
+def truncate_safe(text: str, max_chars: int, ellipsis: str = "\u2026") -> str:
+    """
+    Shorten text for display without splitting a character sequence.
+
+    The cut never separates a base character from its combining marks,
+    variation selectors or skin tone modifiers, never breaks a zero-width-joiner
+    emoji sequence, and never splits a surrogate pair. The ellipsis counts
+    toward max_chars.
+
+    Example usage:
+    for path, delta in parser.take_deltas():
+        preview[path] = truncate_safe(preview.get(path, "") + delta, 80)
+
+    Args:
+        text: The (possibly partial) string to shorten
+        max_chars: Maximum length of the result, ellipsis included
+        ellipsis: Appended when text is cut
+
+    Returns:
+        text unchanged if it fits, otherwise its longest safe prefix plus ellipsis
+    """
+    if len(text) <= max_chars:
+        return text
+    cut = max(max_chars - len(ellipsis), 0)
+    while cut > 0 and (_attaches_to_previous(text[cut]) or text[cut - 1] == _ZWJ):
+        cut -= 1
+    return text[:cut] + ellipsis[:max_chars]
+
+
+def _event_text(event: Any) -> str:
+    """Pull the generated text out of one decoded streaming event."""
+    if not isinstance(event, dict):
+        return ""
+    # Anthropic messages: {"type": "content_block_delta", "delta": {"type": "text_delta", "text": ...}}
+    if event.get("type") == "content_block_delta":
+        delta = event.get("delta") or {}
+        if delta.get("type") != "text_delta":
+            return ""
+        return delta.get("text") or ""
+    # OpenAI chat completions: {"choices": [{"delta": {"content": ...}}]}
+    # and legacy completions: {"choices": [{"text": ...}]}
+    choices = event.get("choices") or []
+    if choices and isinstance(choices[0], dict):
+        choice = choices[0]
+        delta = choice.get("delta")
+        if isinstance(delta, dict):
+            return delta.get("content") or ""
+        return choice.get("text") or ""
+    return ""
+
+
+class SseAdapter:
+    """
+    Feed a parser straight from a Server-Sent Events response body.
+
+    Raw bytes are split into events at blank lines, the JSON in each event's
+    data field is decoded, and the generated text (OpenAI choices[0].delta.content
+    or Anthropic text_delta) is fed to the parser. Events may be split across
+    chunks at any byte. Other events, comments and keep-alives are skipped.
+
+    Example usage:
+    adapter = SseAdapter(Parser(Person))
+    for chunk in response.iter_bytes():
+        partial = adapter.feed(chunk)
+    """
+
+    def __init__(self, parser: Any):
+        self.parser = parser
+        self.done = False
+        self._decoder = codecs.getincrementaldecoder("utf-8")(errors="replace")
+        self._buffer = ""
+
+    def feed(self, data: Union[bytes, str]) -> Optional[Any]:
+        """
+        Feed the next chunk of the response body.
+
+        Returns:
+            The parser's current object after the last text fed from this
+            chunk, or None when the chunk completed no event with text
+        """
+        if isinstance(data, bytes):
+            data = self._decoder.decode(data)
+        self._buffer = (self._buffer + data).replace("\r\n", "\n")
+        result = None
+        while "\n\n" in self._buffer:
+            block, self._buffer = self._buffer.split("\n\n", 1)
+            text = self._block_text(block)
+            if text:
+                result = self.parser.feed(text)
+        return result
+
+    def _block_text(self, block: str) -> str:
+        # Multiple data lines in one event are joined with newlines
+        data_lines = []
+        for line in block.split("\n"):
+            if line.startswith("data:"):
+                value = line[len("data:"):]
+                data_lines.append(value[1:] if value.startswith(" ") else value)
+        if not data_lines:
+            return ""
+        payload = "\n".join(data_lines)
+        if payload.strip() == "[DONE]":
+            self.done = True
+            return ""
+        try:
+            event = json.loads(payload)
+        except ValueError:
+            return ""
+        if isinstance(event, dict) and event.get("type") == "message_stop":
+            self.done = True
+        return _event_text(event)
+
+
+def parse_stream(parser: Any, chunks: Iterable[str]) -> Iterator[Any]:
+    """
+    Feed chunks from a regular iterable into a parser.
+
+    The synchronous counterpart of parse_async_stream, for use in for-loops
+    and with itertools.
+
+    Example usage:
+    for partial in parse_stream(Parser(Person), response_chunks):
+        render(partial)
+
+    Args:
+        parser: The gasp Parser to feed
+        chunks: Iterable of text chunks, e.g. from a blocking LLM client
+
+    Yields:
+        The partial (and finally complete) parsed object
+    """
+    for chunk in chunks:
+        result = parser.feed(chunk)
+        if result is not None:
+            yield result
+        if parser.is_complete():
+            break
+
+
+class ChannelSink:
+    """
+    Push a parser's snapshots into a bounded queue.Queue for a worker thread.
+
+    overflow decides what happens when the queue is full:
+    - "block": wait for the consumer (back-pressure on the producer)
+    - "drop_oldest": discard the oldest pending snapshot
+    - "coalesce_latest": replace the newest pending snapshot, since each
+      snapshot of a value supersedes the one before it
+
+    Example usage:
+    sink = ChannelSink(Parser(Person), queue.Queue(maxsize=8), overflow="coalesce_latest")
+    for chunk in llm_chunks:
+        sink.feed(chunk)
+    """
+
+    OVERFLOW_POLICIES = ("block", "drop_oldest", "coalesce_latest")
+
+    def __init__(self, parser: Any, channel: "queue.Queue[Any]", overflow: str = "block"):
+        if overflow not in self.OVERFLOW_POLICIES:
+            raise ValueError(f"Unknown overflow policy {overflow!r}; expected one of {self.OVERFLOW_POLICIES}")
+        self.parser = parser
+        self.channel = channel
+        self.overflow = overflow
+
+    def feed(self, chunk: str) -> Optional[Any]:
+        """Feed a chunk and push the resulting snapshot, if any. Returns the snapshot."""
+        result = self.parser.feed(chunk)
+        if result is not None:
+            self.push(result)
+        return result
+
+    def push(self, item: Any) -> None:
+        if self.overflow == "block":
+            self.channel.put(item)
+            return
+        while True:
+            try:
+                self.channel.put_nowait(item)
+                return
+            except queue.Full:
+                pass
+            if self.overflow == "coalesce_latest":
+                with self.channel.mutex:
+                    if self.channel.queue:
+                        self.channel.queue[-1] = item
+                        return
+            else:
+                try:
+                    self.channel.get_nowait()
+                except queue.Empty:
+                    pass
+
+
+class SnapshotWatch:
+    """
+    Share the latest snapshot of a parser with other threads.
+
+    The feeding thread calls feed() (and finish() at the end); readers call
+    latest() to poll, or wait() to block until a newer snapshot than the one
+    they last saw is published. Unlike ChannelSink nothing is queued: a
+    slow reader just skips the snapshots it missed.
+
+    Example usage:
+    watch = SnapshotWatch(Parser(Person))
+    # reader thread
+    version, person, done = watch.wait()
+    while not done:
+        render(person)
+        version, person, done = watch.wait(version)
+    """
+
+    def __init__(self, parser: Any):
+        self.parser = parser
+        self._changed = threading.Condition()
+        self._version = 0
+        self._value: Optional[Any] = None
+        self._done = False
+
+    def feed(self, chunk: str) -> Optional[Any]:
+        """Feed a chunk and publish the resulting snapshot, if any. Returns the snapshot."""
+        result = self.parser.feed(chunk)
+        if result is not None:
+            self.publish(result)
+        return result
+
+    def finish(self) -> Optional[Any]:
+        """Finish the parser, publish its final value and wake every reader."""
+        result = self.parser.finish()
+        self.publish(result, done=True)
+        return result
+
+    def publish(self, value: Any, done: bool = False) -> None:
+        with self._changed:
+            self._version += 1
+            self._value = value
+            self._done = self._done or done
+            self._changed.notify_all()
+
+    def latest(self) -> Tuple[int, Optional[Any], bool]:
+        """(version, snapshot, done) as of now; version 0 means nothing yet."""
+        with self._changed:
+            return self._version, self._value, self._done
+
+    def wait(self, after: int = 0, timeout: Optional[float] = None) -> Tuple[int, Optional[Any], bool]:
+        """
+        Block until a snapshot newer than version after is published, or the
+        stream is done, then return latest(). On timeout, returns latest()
+        as it stands.
+        """
+        with self._changed:
+            self._changed.wait_for(lambda: self._version > after or self._done, timeout)
+            return self._version, self._value, self._done
+
+
+MERGE_CONFLICT_POLICIES = ("first", "second", "error")
+
+
+def merge_results(
+    first: Any, second: Any, schema: Any, conflict: str = "first", epsilon: float = 0.0
+) -> Tuple[Any, Dict[str, List[str]]]:
+    """
+    Combine the partial result of a truncated generation with a retry.
+
+    Fields missing from the first (None, or never set) are taken from the
+    second; lists the first stopped partway through are extended with the
+    second's later items, and a string the second continues is completed.
+    Neither input is modified. Numbers within epsilon of each other count
+    as the same value, so float drift between attempts is not a conflict.
+
+    conflict decides what happens when both have a different value:
+    - "first": keep the first attempt's value
+    - "second": take the retry's value
+    - "error": raise ValueError
+
+    Example usage:
+    merged, report = merge_results(parser.finish(), retry_parser.finish(), Person)
+    report["filled"]  # ["Person.email"]
+
+    Args:
+        first: The (possibly partial) result of the first attempt
+        second: The result of the retried generation
+        schema: The type both were parsed as
+        conflict: One of MERGE_CONFLICT_POLICIES
+        epsilon: Largest difference between two numbers still treated as equal
+
+    Returns:
+        (merged value, report), where the report lists paths under
+        "filled", "extended" and "conflicts"
+    """
+    if conflict not in MERGE_CONFLICT_POLICIES:
+        raise ValueError(f"Unknown conflict policy {conflict!r}; expected one of {MERGE_CONFLICT_POLICIES}")
+    if epsilon < 0:
+        raise ValueError(f"epsilon must be >= 0, got {epsilon!r}")
+    report: Dict[str, List[str]] = {"filled": [], "extended": [], "conflicts": []}
+    merged = _merge(first, second, schema, getattr(schema, "__name__", "root"), conflict, epsilon, report)
+    return merged, report
+
+
+def _field_types(cls: Any) -> Dict[str, Any]:
+    try:
+        return typing.get_type_hints(cls)
+    except Exception:
+        return dict(getattr(cls, "__annotations__", {}))
+
+
+def _numbers_close(first: Any, second: Any, epsilon: float) -> bool:
+    numbers = (int, float, decimal.Decimal)
+    if isinstance(first, bool) or isinstance(second, bool):
+        return False
+    if not (isinstance(first, numbers) and isinstance(second, numbers)):
+        return False
+    try:
+        return abs(first - second) <= epsilon
+    except TypeError:
+        # Decimal and float don't subtract directly
+        return abs(float(first) - float(second)) <= epsilon
+
+
+def _merge(
+    first: Any, second: Any, schema: Any, path: str, conflict: str, epsilon: float, report: Dict[str, List[str]]
+) -> Any:
+    if first is None:
+        if second is not None:
+            report["filled"].append(path)
+        return second
+    if second is None:
+        return first
+
+    origin = typing.get_origin(schema)
+    args = typing.get_args(schema)
+    if origin is Union:
+        # Merge as whichever member both values are, e.g. the T of Optional[T]
+        members = [arg for arg in args if arg is not type(None)]
+        same = [arg for arg in members if isinstance(arg, type) and isinstance(first, arg) and isinstance(second, arg)]
+        schema = same[0] if same else members[0] if len(members) == 1 else Any
+        origin = typing.get_origin(schema)
+        args = typing.get_args(schema)
+
+    if origin is list and isinstance(first, list) and isinstance(second, list):
+        item_type = args[0] if args else Any
+        merged = [
+            _merge(a, b, item_type, f"{path}[{i}]", conflict, epsilon, report)
+            for i, (a, b) in enumerate(zip(first, second))
+        ]
+        merged.extend(first[len(second):])
+        if len(second) > len(first):
+            report["extended"].append(path)
+            merged.extend(second[len(first):])
+        return merged
+    if origin is dict and isinstance(first, dict) and isinstance(second, dict):
+        value_type = args[1] if len(args) == 2 else Any
+        merged = dict(first)
+        for key, value in second.items():
+            merged[key] = _merge(first.get(key), value, value_type, f"{path}[{key}]", conflict, epsilon, report)
+        return merged
+    if isinstance(schema, type) and isinstance(first, schema) and isinstance(second, schema) and _field_types(schema):
+        merged = copy.copy(first)
+        for name, field_type in _field_types(schema).items():
+            value = _merge(
+                getattr(first, name, None),
+                getattr(second, name, None),
+                field_type,
+                f"{path}.{name}",
+                conflict,
+                epsilon,
+                report,
+            )
+            if value is not None:
+                setattr(merged, name, value)
+        return merged
+
+    if first == second or _numbers_close(first, second, epsilon):
+        return first
+    if isinstance(first, str) and isinstance(second, str) and second.startswith(first):
+        # The first attempt was cut off partway through this string
+        report["extended"].append(path)
+        return second
+    report["conflicts"].append(path)
+    if conflict == "error":
+        raise ValueError(f"Conflicting values at {path}: {first!r} != {second!r}")
+    return second if conflict == "second" else first
+
+
+async def parse_async_stream(parser: Any, chunks: AsyncIterable[str]) -> AsyncIterator[Any]:
+    """
+    Feed chunks from an async iterable into a parser.
+
+    Yields the current partial object after every chunk that produced one.
+    Iteration stops once the parser reports completion or the chunks run out.
+
+    Example usage:
+    async for partial in parse_async_stream(Parser(Person), llm_chunks()):
+        render(partial)
+
+    Args:
+        parser: The gasp Parser to feed
+        chunks: Async iterable of text chunks, e.g. from an LLM client
+
+    Yields:
+        The partial (and finally complete) parsed object
+    """
+    async for chunk in chunks:
+        result = parser.feed(chunk)
+        if result is not None:
+            yield result
+        if parser.is_complete():
+            break
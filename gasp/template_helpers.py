@@ -3,6 +3,7 @@ Helpers for generating type-specific format instructions for LLM prompts.
 Updated to reflect the actual XML format expected by the parser.
 """
 import inspect
+import re
 import typing
 import types
 from typing import Any, Dict, List, Optional, Tuple, Set, Type, Union, get_type_hints, get_origin, get_args
@@ -136,6 +137,7 @@ def _format_class_type(cls: Type, tag_name: str, structure_examples: Dict[str, s
     
     # Get docstrings for fields if available
     field_docs = _extract_field_docs(cls)
+    constraints = _field_constraints(cls)
     
     # Build the XML structure
     fields = []
@@ -227,7 +229,7 @@ def _format_class_type(cls: Type, tag_name: str, structure_examples: Dict[str, s
             # Regular fields
             field_format = f'{comment}<{field_name} type="{type_attr}">{example_value}</{field_name}>'
         
-        fields.append(field_format)
+        fields.append(field_format + constraints.get(field_name, ""))
         
         # Track complex nested types
         if origin is list:
@@ -261,6 +263,36 @@ def _format_class_type(cls: Type, tag_name: str, structure_examples: Dict[str, s
     # Return format for use in main output
     return f"<{tag_name}>\n    ...{class_name} fields...\n</{tag_name}>"
 
+def _field_constraints(cls: Type) -> Dict[str, str]:
+    """Describe the Annotated constraints (e.g. MaxLength, Min) of each field."""
+    try:
+        hints = get_type_hints(cls, include_extras=True)
+    except TypeError:
+        return {}
+
+    result = {}
+    for field_name, field_type in hints.items():
+        parts = []
+        for constraint in getattr(field_type, "__metadata__", ()):
+            min_length = getattr(constraint, "min_length", None)
+            if isinstance(min_length, int):
+                parts.append(f"at least {min_length} characters")
+            max_length = getattr(constraint, "max_length", None)
+            if isinstance(max_length, int):
+                parts.append(f"max {max_length} characters")
+            pattern = getattr(constraint, "pattern", None)
+            if isinstance(pattern, str):
+                parts.append(f"matching {pattern}")
+            ge = getattr(constraint, "ge", None)
+            if isinstance(ge, (int, float)):
+                parts.append(f">= {ge}")
+            le = getattr(constraint, "le", None)
+            if isinstance(le, (int, float)):
+                parts.append(f"<= {le}")
+        if parts:
+            result[field_name] = f" ({', '.join(parts)})"
+    return result
+
 def _format_class_fields(cls: Type, indent: str = "") -> str:
     """Format just the fields of a class for inline use."""
     try:
@@ -268,13 +300,14 @@ def _format_class_fields(cls: Type, indent: str = "") -> str:
     except TypeError:
         return ""
     
+    constraints = _field_constraints(cls)
     fields = []
     for field_name, field_type in hints.items():
         if field_name.startswith('_'):
             continue
         type_attr = _get_xml_type_attr(field_type)
         example_value = _get_example_value(field_type)
-        fields.append(f'{indent}<{field_name} type="{type_attr}">{example_value}</{field_name}>')
+        fields.append(f'{indent}<{field_name} type="{type_attr}">{example_value}</{field_name}>{constraints.get(field_name, "")}')
     
     return f"\n{indent}".join(fields)
 
@@ -290,6 +323,7 @@ def _generate_class_structure_example(cls: Type, structure_examples: Dict[str, s
     if not hints:
         return f"<{class_name}>\n</{class_name}>"
     
+    constraints = _field_constraints(cls)
     fields = []
     for field_name, field_type in hints.items():
         if field_name.startswith('_'):
@@ -306,7 +340,7 @@ def _generate_class_structure_example(cls: Type, structure_examples: Dict[str, s
                 non_none_type = next(arg for arg in args if arg is not type(None))
                 type_attr = _get_xml_type_attr(non_none_type)
                 example_value = _get_example_value(non_none_type)
-                fields.append(f'    <{field_name} type="{type_attr}">{example_value}</{field_name}> (optional)')
+                fields.append(f'    <{field_name} type="{type_attr}">{example_value}</{field_name}> (optional){constraints.get(field_name, "")}')
                 
                 # Recursively add nested types
                 if _is_class_type(non_none_type):
@@ -315,7 +349,7 @@ def _generate_class_structure_example(cls: Type, structure_examples: Dict[str, s
                         structure_examples[nested_class_name] = _generate_class_structure_example(non_none_type, structure_examples)
                 continue
         
-        fields.append(f'    <{field_name} type="{type_attr}">{example_value}</{field_name}>')
+        fields.append(f'    <{field_name} type="{type_attr}">{example_value}</{field_name}>{constraints.get(field_name, "")}')
 
         # Recursively add nested types
         if origin is list:
@@ -457,8 +491,10 @@ def _format_list_type(list_type: Type, tag_name: str, structure_examples: Dict[s
                 if arg_name not in structure_examples:
                     structure_examples[arg_name] = _generate_class_structure_example(arg, structure_examples)
         
-        # Show generic type attribute for union
-        return f'<{tag_name} type="list[{item_type_name}]">\n    <item type="... some type from {item_type_name} ...">...</item>\n    <item type="... some type from {item_type_name} ...">...</item>\n    ...\n</{tag_name}>'
+        # Show one correctly-shaped item per union member
+        items = [_format_item_example(arg, structure_examples) for arg in _flatten_union_args(union_args)]
+        items_text = '\n'.join(items)
+        return f'<{tag_name} type="list[{item_type_name}]">\n{items_text}\n    ... (any mix of the item types above, in any order)\n</{tag_name}>'
     elif origin is dict:
         # Special handling for List[dict[...]]
         dict_args = get_args(item_type)
@@ -532,21 +568,41 @@ def _format_tuple_type(tuple_type: Type, tag_name: str, structure_examples: Dict
         # Homogeneous tuple like Tuple[int, ...]
         item_type = args[0]
         item_type_name = _get_type_name(item_type)
-        item_example = _get_example_value(item_type)
-        items = [f'    <item type="{item_type_name}">{item_example}</item>' for _ in range(3)]
+        items = [_format_item_example(item_type, structure_examples) for _ in range(3)]
         return f'<{tag_name} type="tuple[{item_type_name}, ...]">\n' + '\n'.join(items) + '\n    ...\n</{tag_name}>'
     else:
-        # Fixed-length tuple
+        # Fixed-length tuple: exactly one item per position, in order
         items = []
         type_names = []
         for i, arg_type in enumerate(args):
-            type_name = _get_type_name(arg_type)
-            type_names.append(type_name)
-            example = _get_example_value(arg_type)
-            items.append(f'    <item type="{type_name}">{example}</item>')
+            type_names.append(_get_type_name(arg_type))
+            items.append(_format_item_example(arg_type, structure_examples))
         type_spec = ", ".join(type_names)
         return f'<{tag_name} type="tuple[{type_spec}]">\n' + '\n'.join(items) + f'\n</{tag_name}>'
 
+def _flatten_union_args(union_args: Tuple[Type, ...]) -> List[Type]:
+    """Union members with None dropped and nested union aliases expanded."""
+    members = []
+    for arg in union_args:
+        if arg is type(None):
+            continue
+        actual_arg = getattr(arg, '__value__', arg)
+        if get_origin(actual_arg) is Union or type(actual_arg).__name__ == 'UnionType':
+            members.extend(_flatten_union_args(get_args(actual_arg)))
+        else:
+            members.append(actual_arg)
+    return members
+
+def _format_item_example(item_type: Type, structure_examples: Dict[str, str]) -> str:
+    """Format one example <item> of the given type for a list or tuple."""
+    item_type_name = _get_type_name(item_type)
+    if _is_class_type(item_type):
+        class_name = getattr(item_type, "__name__", "Object")
+        if class_name not in structure_examples:
+            structure_examples[class_name] = _generate_class_structure_example(item_type, structure_examples)
+        return f'    <item type="{item_type_name}">\n        ...{class_name} fields...\n    </item>'
+    return f'    <item type="{item_type_name}">{_get_example_value(item_type)}</item>'
+
 def _format_set_type(set_type: Type, tag_name: str, structure_examples: Dict[str, str]) -> str:
     """Format instructions for a Set type."""
     args = get_args(set_type)
@@ -720,24 +776,45 @@ def _extract_field_docs(cls: Type) -> Dict[str, str]:
     
     return result
 
-def interpolate_prompt(template: str, type_obj: Any, format_tag: str = "return_type", name: Optional[str] = None) -> str:
+_PLACEHOLDER = re.compile(r"(\\?)\{\{\s*([^{}]*?)\s*\}\}")
+
+def interpolate_prompt(template: str, type_obj: Any, format_tag: str = "return_type", name: Optional[str] = None,
+                       strict: bool = False) -> str:
     """
     Replace {{format_tag}} in the template with format instructions for the type.
+
+    Whitespace just inside the braces is ignored, so {{ return_type }} is
+    replaced too. A placeholder written as \\{{...}} is kept literally, minus
+    the backslash.
+    Other placeholders are left as they are, unless strict is set.
     
     Args:
         template: The prompt template with {{format_tag}} placeholders
         type_obj: The Python type to generate instructions for
         format_tag: The tag to replace (default: "return_type")
         name: Optional name to use for the type tag (defaults to class name)
+        strict: Raise ValueError, with its offset in the template, for any
+            placeholder other than {{format_tag}}
         
     Returns:
         The interpolated prompt
     """
-    placeholder = "{{" + format_tag + "}}"
-    
-    if placeholder not in template:
-        return template
-    
-    instructions = type_to_format_instructions(type_obj, name=name)
-    
-    return template.replace(placeholder, instructions)
+    instructions = None
+
+    def replace(match: "re.Match[str]") -> str:
+        nonlocal instructions
+        escaped, tag = match.group(1), match.group(2)
+        if escaped:
+            return match.group(0)[1:]
+        if tag == format_tag:
+            if instructions is None:
+                instructions = type_to_format_instructions(type_obj, name=name)
+            return instructions
+        if strict:
+            raise ValueError(
+                f"Unknown placeholder {match.group(0)!r} at offset {match.start()} "
+                f"in prompt template; expected {{{{{format_tag}}}}}"
+            )
+        return match.group(0)
+
+    return _PLACEHOLDER.sub(replace, template)
@@ -0,0 +1,58 @@
+/*
+ * C ABI for the GASP tag scanner.
+ *
+ * Build with: cargo build --release --no-default-features --features ffi
+ * and link against target/release/libgasp.so.
+ */
+#ifndef GASP_TAG_FINDER_H
+#define GASP_TAG_FINDER_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define GASP_TAG_OPEN 0
+#define GASP_TAG_BYTES 1
+#define GASP_TAG_CLOSE 2
+
+#define GASP_OK 0
+#define GASP_ERR_NULL (-1)
+#define GASP_ERR_SCAN (-2)
+
+/* Valid only during the callback. name is set for opens and closes, text for
+ * bytes; neither is NUL-terminated. offset is the byte offset in the whole
+ * stream where the tag (or text) starts. */
+typedef struct GaspTagEvent {
+    uint32_t kind;
+    size_t offset;
+    size_t depth;
+    const char *name;
+    size_t name_len;
+    const char *text;
+    size_t text_len;
+} GaspTagEvent;
+
+typedef void (*GaspTagEventCallback)(void *user_data, const GaspTagEvent *event);
+
+typedef struct GaspTagFinder GaspTagFinder;
+
+/* wanted may be empty to report every tag that is not ignored; entries may be
+ * globs such as "tool_*". Tags are matched case-insensitively. */
+GaspTagFinder *gasp_tag_finder_new(const char *const *wanted, size_t wanted_len,
+                                   const char *const *ignored, size_t ignored_len);
+
+/* Feed the next len bytes of UTF-8 text. A character split across pushes is
+ * held until the rest arrives. Returns GASP_OK or a negative GASP_ERR_*. */
+int gasp_tag_finder_push(GaspTagFinder *finder, const char *chunk, size_t len,
+                         GaspTagEventCallback callback, void *user_data);
+
+void gasp_tag_finder_free(GaspTagFinder *finder);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,38 @@
+#!/usr/bin/env python3
+"""
+Test buffered_bytes() accounting for input the parser is still holding
+"""
+
+from gasp import Parser, Deserializable
+
+
+class Message(Deserializable):
+    text: str
+
+
+def test_buffered_bytes_tracks_open_field_text():
+    parser = Parser(Message)
+    assert parser.buffered_bytes() == 0
+
+    parser.feed("<Message><text>hello")
+    assert parser.buffered_bytes() == len("hello")
+
+    parser.feed("</text></Message>")
+    assert parser.buffered_bytes() == 0
+
+
+def test_buffered_bytes_counts_split_tag_and_partial_utf8():
+    parser = Parser(Message)
+    parser.feed("<Message><te")
+    assert parser.buffered_bytes() == len("<te")
+
+    parser.feed_bytes(b"xt>\xf0\x9f")
+    assert parser.buffered_bytes() == 2
+
+
+def test_finish_releases_buffers():
+    parser = Parser(Message)
+    parser.feed("<Message><text>cut off</te")
+    assert parser.buffered_bytes() > 0
+    parser.finish()
+    assert parser.buffered_bytes() == 0
@@ -0,0 +1,25 @@
+#!/usr/bin/env python3
+"""
+Test the build capability report
+"""
+
+from gasp import Parser, capabilities
+
+
+def test_capabilities_shape():
+    caps = capabilities()
+    assert isinstance(caps["version"], str) and caps["version"]
+    assert caps["formats"] == ["xml"]
+    assert set(caps["features"]) == {"ffi", "intern", "metrics", "serde", "serde_json", "tracing", "unsafe_debug"}
+    assert all(isinstance(v, bool) for v in caps["features"].values())
+    assert caps["limits"] == {
+        "max_depth": None,
+        "max_string_length": None,
+        "max_nodes": None,
+        "max_buffer_bytes": None,
+    }
+
+
+def test_metrics_feature_matches_parser():
+    """The metrics flag agrees with the methods the Parser exposes"""
+    assert capabilities()["features"]["metrics"] == hasattr(Parser, "get_metrics")
@@ -0,0 +1,73 @@
+#!/usr/bin/env python3
+"""
+Test pushing parser snapshots into a bounded queue
+"""
+
+import queue
+import threading
+
+import pytest
+
+from gasp import ChannelSink, Parser, Deserializable
+
+
+class Note(Deserializable):
+    text: str
+
+
+def _drain(channel):
+    items = []
+    while not channel.empty():
+        items.append(channel.get_nowait())
+    return items
+
+
+def test_drop_oldest_keeps_newest():
+    channel = queue.Queue(maxsize=2)
+    sink = ChannelSink(Parser(str), channel, overflow="drop_oldest")
+    for item in ["a", "b", "c", "d"]:
+        sink.push(item)
+    assert _drain(channel) == ["c", "d"]
+
+
+def test_coalesce_latest_replaces_newest_pending():
+    channel = queue.Queue(maxsize=2)
+    sink = ChannelSink(Parser(str), channel, overflow="coalesce_latest")
+    for item in ["a", "b", "c", "d"]:
+        sink.push(item)
+    assert _drain(channel) == ["a", "d"]
+
+
+def test_block_waits_for_consumer():
+    channel = queue.Queue(maxsize=1)
+    sink = ChannelSink(Parser(Note), channel, overflow="block")
+    received = []
+
+    def consume():
+        while True:
+            item = channel.get()
+            if item is None:
+                return
+            received.append(item)
+
+    worker = threading.Thread(target=consume)
+    worker.start()
+    for chunk in ["<Note><text>he", "llo</text>", "</Note>"]:
+        sink.feed(chunk)
+    channel.put(None)
+    worker.join(timeout=5)
+
+    assert len(received) == 3
+    assert received[-1].text == "hello"
+
+
+def test_feed_skips_empty_results():
+    channel = queue.Queue()
+    sink = ChannelSink(Parser(Note), channel)
+    assert sink.feed("no tags here") is None
+    assert channel.empty()
+
+
+def test_unknown_policy_rejected():
+    with pytest.raises(ValueError, match="Unknown overflow policy"):
+        ChannelSink(Parser(Note), queue.Queue(), overflow="spill")
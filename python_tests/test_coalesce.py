@@ -0,0 +1,58 @@
+"""Tests for coalesce_bytes, which batches tiny chunks into one parse step."""
+
+from typing import List
+
+from gasp import Deserializable, Parser
+
+
+class Note(Deserializable):
+    title: str
+    body: str
+
+
+TEXT = "<Note><title>Groceries</title><body>eggs, milk, bread</body></Note>"
+
+
+def chunks(text, size=3):
+    return [text[i:i + size] for i in range(0, len(text), size)]
+
+
+def test_same_result_as_uncoalesced():
+    plain = Parser(Note)
+    coalesced = Parser(Note, coalesce_bytes=32)
+    for chunk in chunks(TEXT):
+        plain.feed(chunk)
+        coalesced.feed(chunk)
+    for parser in (plain, coalesced):
+        note = parser.get_partial()
+        assert (note.title, note.body) == ("Groceries", "eggs, milk, bread")
+    assert coalesced.is_complete()
+
+
+def test_fewer_steps():
+    parser = Parser(Note, coalesce_bytes=1000)
+    for chunk in chunks("<Note><body>" + "x" * 300):
+        parser.feed(chunk)
+    # Only the chunk ending "<body>" and nothing after it has been parsed
+    assert parser.get_partial().body == ""
+    assert parser.buffered_bytes() >= 300
+
+
+def test_tag_end_flushes():
+    parser = Parser(Note, coalesce_bytes=1000)
+    parser.feed("<Note><title>Hi")
+    parser.feed("</title>")
+    assert parser.get_partial().title == "Hi"
+
+
+def test_finish_flushes_pending():
+    parser = Parser(List[str], coalesce_bytes=1000)
+    parser.feed("<list><item>a</item><item>b")
+    assert parser.finish() == ["a", "b"]
+
+
+def test_off_by_default():
+    parser = Parser(Note)
+    parser.feed("<Note><body>ab")
+    parser.feed("c")
+    assert parser.get_partial().body == "abc"
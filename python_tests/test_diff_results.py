@@ -0,0 +1,62 @@
+"""Tests for diff_results and SnapshotDiffer."""
+
+import copy
+from typing import Dict, List
+
+from gasp import Deserializable, Parser
+from gasp.paths import Change, SnapshotDiffer, diff_results, get_path
+
+
+class Address(Deserializable):
+    city: str
+
+
+class Person(Deserializable):
+    name: str
+    address: Address
+    tags: List[str]
+    extra: Dict[str, str]
+
+
+def test_diff_between_snapshots():
+    parser = Parser(Person)
+    before = copy.deepcopy(parser.feed("<Person><name>Ad"))
+    after = parser.feed("a</name><tags type=\"list\"><item>x</item>")
+    assert diff_results(before, after) == [
+        Change("modified", "name", "Ad", "Ada"),
+        Change("added", "tags[0]", None, "x"),
+    ]
+
+
+def test_nested_lists_and_dicts():
+    old = {"a": [1, 2], "b.c": {"x": 1}, "gone": True}
+    new = {"a": [1, 3, 4], "b.c": {"x": 1, "y": 2}}
+    changes = diff_results(old, new)
+    assert changes == [
+        Change("modified", "a[1]", 2, 3),
+        Change("added", "a[2]", None, 4),
+        Change("added", '["b.c"].y', None, 2),
+        Change("removed", "gone", True, None),
+    ]
+    # Every path resolves on the side that has the value
+    for change in changes:
+        side = old if change.kind == "removed" else new
+        expected = change.old if change.kind == "removed" else change.new
+        assert get_path(side, change.path) == expected
+
+
+def test_type_change_is_a_modification():
+    assert diff_results({"a": 1}, {"a": "1"}) == [Change("modified", "a", 1, "1")]
+    assert diff_results(None, 5) == [Change("modified", "", None, 5)]
+    assert diff_results([1], [1]) == []
+
+
+def test_snapshot_differ_copies_in_place_updates():
+    parser = Parser(Person)
+    differ = SnapshotDiffer()
+    first = differ.update(parser.feed("<Person><address><city>Lon"))
+    assert [change.kind for change in first] == ["modified"]
+    assert differ.update(parser.feed("don</city>")) == [
+        Change("modified", "address.city", "Lon", "London")
+    ]
+    assert differ.update(parser.feed("</address>")) == []
@@ -0,0 +1,60 @@
+#!/usr/bin/env python3
+"""
+Test the policy for object fields and dict keys that appear twice
+"""
+
+from typing import Dict
+
+import pytest
+
+from gasp import Parser, Deserializable
+
+
+class Transfer(Deserializable):
+    to: str
+    amount: int
+
+
+class Config(Deserializable):
+    settings: Dict[str, str]
+
+
+TRANSFER = "<Transfer><to>alice</to><amount>5</amount><to>mallory</to><to>eve</to></Transfer>"
+CONFIG = '<Config><settings><item key="mode">safe</item><item key="mode">unsafe</item></settings></Config>'
+
+
+def _parse(xml, cls, **kwargs):
+    parser = Parser(cls, **kwargs)
+    parser.feed(xml)
+    return parser.validate()
+
+
+def test_last_wins_by_default():
+    """The historical behavior: later values replace earlier ones"""
+    assert _parse(TRANSFER, Transfer).to == "eve"
+    assert _parse(CONFIG, Config).settings == {"mode": "unsafe"}
+
+
+def test_first_wins():
+    assert _parse(TRANSFER, Transfer, duplicate_keys="first_wins").to == "alice"
+    assert _parse(CONFIG, Config, duplicate_keys="first_wins").settings == {"mode": "safe"}
+
+
+def test_error():
+    with pytest.raises(ValueError, match="Duplicate field 'to' in <Transfer>"):
+        _parse(TRANSFER, Transfer, duplicate_keys="error")
+    with pytest.raises(ValueError, match="Duplicate key mode in <settings>"):
+        _parse(CONFIG, Config, duplicate_keys="error")
+
+
+def test_collect_into_array():
+    """Every value is kept, in order"""
+    transfer = _parse(TRANSFER, Transfer, duplicate_keys="collect")
+    assert transfer.to == ["alice", "mallory", "eve"]
+    assert transfer.amount == 5
+    assert _parse(CONFIG, Config, duplicate_keys="collect").settings == {"mode": ["safe", "unsafe"]}
+
+
+def test_unknown_policy_rejected():
+    with pytest.raises(ValueError, match="Unknown duplicate_keys policy"):
+        Parser(Transfer, duplicate_keys="newest")
@@ -0,0 +1,37 @@
+#!/usr/bin/env python3
+"""
+Test completing a class root before its closing tag arrives
+"""
+
+from typing import List
+
+from gasp import Parser, Deserializable
+
+
+class Action(Deserializable):
+    name: str
+    args: List[str]
+
+
+def test_eager_complete_after_last_field():
+    """The result is complete once every field has closed"""
+    parser = Parser(Action, eager_complete=True)
+    parser.feed("<Action><name>search</name>")
+    assert not parser.is_complete()
+
+    result = parser.feed("<args><item>cats</item></args>")
+    assert parser.is_complete()
+    assert result.name == "search"
+    assert result.args == ["cats"]
+
+    # The closing tag arriving later does not change the result
+    assert parser.feed("</Action>").name == "search"
+
+
+def test_default_waits_for_close_tag():
+    """Without eager_complete the root close tag is required"""
+    parser = Parser(Action)
+    parser.feed("<Action><name>search</name><args><item>cats</item></args>")
+    assert not parser.is_complete()
+    parser.feed("</Action>")
+    assert parser.is_complete()
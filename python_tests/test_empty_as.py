@@ -0,0 +1,85 @@
+#!/usr/bin/env python3
+"""
+Test per-tag handling of empty and whitespace-only tags
+"""
+
+from typing import List, Optional
+
+import pytest
+
+from gasp import Parser, Deserializable
+
+
+class Answer(Deserializable):
+    name: str
+    age: int
+
+
+class Report(Deserializable):
+    title: str
+    summary: Optional[str]
+    tags: List[str]
+
+
+def test_default_behavior_unchanged():
+    """Without a policy a whitespace-only string field keeps its text"""
+    parser = Parser(Report)
+    parser.feed("<Report><title>T</title><summary>   </summary><tags></tags></Report>")
+    report = parser.validate()
+    assert report.summary == "   "
+    assert report.tags == []
+    assert parser.empty_paths() == []
+
+
+def test_policies_per_tag():
+    """Each empty tag follows the policy configured for its name"""
+    parser = Parser(Report, empty_as={"summary": "null", "title": "empty_string", "tags": "empty_object"})
+    parser.feed("<Report><title> </title><summary>\n  </summary><tags> </tags></Report>")
+    report = parser.validate()
+    assert report.title == ""
+    assert report.summary is None
+    assert report.tags == []
+    assert parser.empty_paths() == [
+        ("Report.title", "empty_string"),
+        ("Report.summary", "null"),
+        ("Report.tags", "empty_object"),
+    ]
+
+
+def test_non_empty_tags_ignore_policy():
+    """Policies only apply to tags with no content"""
+    parser = Parser(Report, empty_as={"*": "null"})
+    parser.feed("<Report><title>Hi</title><tags><item>a</item></tags></Report>")
+    report = parser.validate()
+    assert report.title == "Hi"
+    assert report.tags == ["a"]
+    assert parser.empty_paths() == []
+
+
+def test_empty_root_object():
+    """An empty root tag can become None or an instance with no fields"""
+    parser = Parser(Answer, empty_as={"Answer": "null"})
+    assert parser.feed("<Answer>   </Answer>") is None
+    assert parser.is_complete()
+
+    parser = Parser(Answer, empty_as={"Answer": "empty_object"})
+    assert isinstance(parser.feed("<Answer>   </Answer>"), Answer)
+
+
+def test_error_policy_raises():
+    """The error policy names the empty tag"""
+    parser = Parser(Answer, empty_as={"*": "error"})
+    with pytest.raises(ValueError, match="<name> is empty"):
+        parser.feed("<Answer><name>  </name>")
+
+
+def test_primitive_root():
+    """Primitive roots follow the policy too"""
+    parser = Parser(str, empty_as={"str": "null"})
+    assert parser.feed("<str>  </str>") is None
+    assert parser.empty_paths() == [("str", "null")]
+
+
+def test_unknown_policy_rejected():
+    with pytest.raises(ValueError, match="Unknown empty_as policy"):
+        Parser(Answer, empty_as={"Answer": "blank"})
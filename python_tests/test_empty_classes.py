@@ -39,10 +39,8 @@ def test_empty_class_self_closing_tag():
     parser = Parser(Finalize)
     result = parser.feed(xml)
     
-    # Self-closing tags have a quirk: they return a result but is_complete() is False
-    # This appears to be a limitation of how the XML parser handles self-closing tags
-    assert not parser.is_complete()  # Parser doesn't consider self-closing tags "complete"
-    assert result is not None  # But it does return a valid instance
+    assert parser.is_complete()
+    assert result is not None
     assert isinstance(result, Finalize)
     assert result.__dict__ == {}
 
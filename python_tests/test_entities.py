@@ -0,0 +1,44 @@
+#!/usr/bin/env python3
+"""
+Test XML entity decoding in string values
+"""
+
+from gasp import Parser, Deserializable
+
+
+class Message(Deserializable):
+    text: str
+
+
+def parse_text(*chunks):
+    parser = Parser(Message)
+    result = None
+    for chunk in chunks:
+        result = parser.feed(chunk)
+    return result.text
+
+
+def test_named_entities():
+    assert parse_text("<Message><text>&lt;b&gt; &amp; &quot;q&quot; &apos;a&#39;</text></Message>") == '<b> & "q" \'a\''
+
+
+def test_escaped_ampersand_is_decoded_once():
+    """&amp;lt; is the literal text &lt;, not <"""
+    assert parse_text("<Message><text>&amp;lt;tag&amp;gt;</text></Message>") == "&lt;tag&gt;"
+
+
+def test_numeric_references():
+    assert parse_text("<Message><text>caf&#233; &#x1F600;</text></Message>") == "café 😀"
+
+
+def test_surrogate_pair_references():
+    """UTF-16 surrogate pairs written as two references combine into one character"""
+    assert parse_text("<Message><text>&#xD83D;&#xDE00; &#55357;&#56832;</text></Message>") == "😀 😀"
+
+
+def test_surrogate_pair_split_across_chunks():
+    assert parse_text("<Message><text>hi &#xD83D;", "&#xDE00;!</text></Message>") == "hi 😀!"
+
+
+def test_lone_surrogates_and_unknown_entities():
+    assert parse_text("<Message><text>&#xD83D;x &#xDE00; AT&T &nbsp;</text></Message>") == "�x � AT&T &nbsp;"
@@ -0,0 +1,53 @@
+"""Tests for the position and path attached to parse errors."""
+
+from typing import List
+
+import pytest
+
+from gasp import Parser
+
+
+class Transfer:
+    to: str
+    amount: int
+
+
+class Batch:
+    transfers: List[Transfer]
+
+
+def test_error_reports_line_column_and_path():
+    parser = Parser(Transfer, duplicate_keys="error")
+    with pytest.raises(ValueError, match=r"at line 3, column 8 \(byte 31\), in Transfer$"):
+        parser.feed("<Transfer>\n  <to>a</to>\n  <to>b</to>")
+
+
+def test_error_attributes():
+    parser = Parser(Batch, empty_as={"to": "error"})
+    parser.feed("<Batch><transfers>\n")
+    try:
+        parser.feed("<item><to> </to></item></transfers></Batch>")
+    except ValueError as err:
+        assert (err.line, err.column, err.offset) == (2, 12, 30)
+        assert err.path == "Batch.transfers[0].to"
+    else:
+        raise AssertionError("expected ValueError")
+
+
+def test_offsets_count_from_stream_start():
+    text = "reasoning first\n<Transfer><to>a</to><to>b</to>"
+    parser = Parser(Transfer, duplicate_keys="error")
+    try:
+        for i in range(0, len(text), 4):
+            parser.feed(text[i:i + 4])
+    except ValueError as err:
+        assert err.offset == text.index("</to><to>b") + len("</to><to>b")
+        assert err.line == 2
+    else:
+        raise AssertionError("expected ValueError")
+
+
+def test_primitive_error_has_no_path():
+    parser = Parser(int, empty_as={"*": "error"})
+    with pytest.raises(ValueError, match=r"<int> is empty at line 2, column 1 \(byte 6\)$"):
+        parser.feed("<int>\n</int>")
@@ -0,0 +1,43 @@
+#!/usr/bin/env python3
+"""
+Test feeding raw bytes split at arbitrary points
+"""
+
+from gasp import Parser, Deserializable
+
+
+class Message(Deserializable):
+    text: str
+
+
+def test_multibyte_characters_split_across_chunks():
+    """Every split point of a multi-byte stream gives the same result"""
+    data = "<Message><text>héllo 😀 wörld</text></Message>".encode("utf-8")
+    for split in range(len(data) + 1):
+        parser = Parser(Message)
+        parser.feed_bytes(data[:split])
+        result = parser.feed_bytes(data[split:])
+        assert parser.is_complete()
+        assert result.text == "héllo 😀 wörld", split
+
+
+def test_byte_at_a_time():
+    data = "<Message><text>日本語</text></Message>".encode("utf-8")
+    parser = Parser(Message)
+    for i in range(len(data)):
+        result = parser.feed_bytes(data[i:i + 1])
+    assert result.text == "日本語"
+
+
+def test_invalid_utf8_is_replaced():
+    """Invalid bytes never raise; they become U+FFFD"""
+    parser = Parser(Message)
+    result = parser.feed_bytes(b"<Message><text>a\xffb\xc3</text></Message>")
+    assert result.text == "a�b�"
+
+
+def test_mixed_str_and_bytes():
+    parser = Parser(Message)
+    parser.feed("<Message><text>ab")
+    result = parser.feed_bytes("c😀</text></Message>".encode("utf-8"))
+    assert result.text == "abc😀"
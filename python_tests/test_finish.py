@@ -0,0 +1,85 @@
+#!/usr/bin/env python3
+"""
+Test explicit end-of-input handling with Parser.finish()
+"""
+
+from typing import List
+
+import pytest
+
+from gasp import Parser, Deserializable
+
+
+class Person(Deserializable):
+    name: str
+    hobbies: List[str]
+
+
+def test_finish_closes_open_tags():
+    """A truncated stream still yields the fields seen so far"""
+    parser = Parser(Person)
+    parser.feed("<Person><name>Alice</name><hobbies><item>chess</item><item>go")
+    assert not parser.is_complete()
+
+    result = parser.finish()
+    assert parser.is_complete()
+    assert result.name == "Alice"
+    assert result.hobbies == ["chess", "go"]
+    assert parser.unclosed_tags() == ["Person", "hobbies", "item"]
+
+
+def test_finish_after_complete_is_noop():
+    """Finishing a complete parse returns the same result"""
+    parser = Parser(Person)
+    parser.feed("<Person><name>Bob</name></Person>")
+    result = parser.finish()
+    assert result.name == "Bob"
+    assert parser.unclosed_tags() == []
+
+
+def test_finish_drops_partial_tag():
+    """A tag cut off mid-name is discarded"""
+    parser = Parser(Person)
+    parser.feed("<Person><name>Carol</name><hob")
+    result = parser.finish()
+    assert result.name == "Carol"
+    assert parser.unclosed_tags() == ["Person"]
+
+
+def test_finish_primitive():
+    """Primitive roots are converted from the content seen so far"""
+    parser = Parser(int)
+    parser.feed("<int>42")
+    assert parser.finish() == 42
+
+
+def test_finish_without_payload():
+    """No wanted tag seen means no result"""
+    parser = Parser(Person)
+    parser.feed("no tags here")
+    assert parser.finish() is None
+    assert parser.is_complete()
+
+
+def test_unclosed_paths_name_each_open_value():
+    """Paths pinpoint the list item and fields left open"""
+    parser = Parser(Person)
+    parser.feed("<Person><name>Alice</name><hobbies><item>chess</item><item>go")
+    parser.finish()
+    assert parser.unclosed_paths() == ["Person", "Person.hobbies", "Person.hobbies[1]"]
+
+
+def test_finish_strict_reports_unclosed():
+    """Strict mode raises instead of returning a best-effort value"""
+    parser = Parser(Person)
+    parser.feed("<Person><name>Ali")
+    with pytest.raises(ValueError, match=r"2 unclosed value\(s\): Person, Person\.name"):
+        parser.finish(strict=True)
+
+
+def test_finish_strict_complete_stream():
+    """Strict mode is silent when everything was closed"""
+    parser = Parser(Person)
+    parser.feed("<Person><name>Bob</name></Person>")
+    assert parser.finish(strict=True).name == "Bob"
+    assert parser.unclosed_paths() == []
@@ -26,6 +26,20 @@ def test_gasp_has_stream_parser():
     assert hasattr(gasp, 'StreamParser'), "StreamParser not found"
 
 
+def test_stream_parser_returns_root_once_closed():
+    """Test that StreamParser.parse returns the root element when it closes"""
+    parser = gasp.StreamParser()
+    assert parser.parse('<Person id="7"><name>Ada</na') is None
+    assert not parser.is_done()
+    value = parser.parse('me></Person>')
+    assert parser.is_done()
+    assert value == {
+        "name": "Person",
+        "attributes": {"id": "7"},
+        "children": [{"name": "name", "attributes": {}, "children": ["Ada"]}],
+    }
+
+
 def test_gasp_has_deserializable():
     """Test that Deserializable is available in gasp module"""
     assert hasattr(gasp, 'Deserializable'), "Deserializable not found"
@@ -0,0 +1,48 @@
+"""Tests for interpolate_prompt escaping and strict placeholder checks."""
+
+import pytest
+
+from gasp import Deserializable
+from gasp.template_helpers import interpolate_prompt, type_to_format_instructions
+
+
+class Person(Deserializable):
+    name: str
+
+
+INSTRUCTIONS = type_to_format_instructions(Person)
+
+
+def test_replaces_every_placeholder():
+    result = interpolate_prompt("A {{return_type}} B {{ return_type }}", Person)
+    assert result == f"A {INSTRUCTIONS} B {INSTRUCTIONS}"
+
+
+def test_whitespace_inside_braces_is_ignored():
+    template = "{{  return_type\t}} {{\nreturn_type }} {{ user }}"
+    result = interpolate_prompt(template, Person)
+    assert result == f"{INSTRUCTIONS} {INSTRUCTIONS} {{{{ user }}}}"
+    # Inner whitespace is not part of the name, in strict mode either
+    with pytest.raises(ValueError, match=r"Unknown placeholder '\{\{ user \}\}' at offset 0"):
+        interpolate_prompt("{{ user }}", Person, strict=True)
+    assert interpolate_prompt("{{ return_type }}", Person, strict=True) == INSTRUCTIONS
+    # Whitespace inside the name itself still doesn't match
+    assert interpolate_prompt("{{return _type}}", Person) == "{{return _type}}"
+
+
+def test_escaped_placeholder_is_literal():
+    result = interpolate_prompt("Write \\{{return_type}} as: {{return_type}}", Person)
+    assert result == "Write {{return_type}} as: " + INSTRUCTIONS
+
+
+def test_other_placeholders_left_alone():
+    assert interpolate_prompt("Hi {{user}}", Person) == "Hi {{user}}"
+
+
+def test_strict_reports_unknown_placeholder_offset():
+    with pytest.raises(ValueError, match=r"Unknown placeholder '\{\{user\}\}' at offset 3"):
+        interpolate_prompt("Hi {{user}} {{return_type}}", Person, strict=True)
+
+
+def test_strict_allows_escapes():
+    assert interpolate_prompt("\\{{user}}", Person, strict=True) == "{{user}}"
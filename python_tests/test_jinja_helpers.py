@@ -0,0 +1,62 @@
+#!/usr/bin/env python3
+"""
+Test Jinja2 template rendering, including strict handling of missing variables
+"""
+
+import jinja2
+import pytest
+
+from gasp import Deserializable, render_file_template, render_template
+
+
+class Person(Deserializable):
+    """A person"""
+    name: str
+
+
+def test_render_template_with_type_filters():
+    result = render_template("Make a {{ t|type_description }}", {"t": Person})
+    assert result == "Make a Person (A person)"
+
+
+def test_missing_variable_renders_empty_by_default():
+    assert render_template("Hi {{ name }}!", {}) == "Hi !"
+
+
+def test_strict_missing_variable_raises():
+    with pytest.raises(jinja2.UndefinedError, match="'name' is undefined"):
+        render_template("Hi {{ name }}!", {}, strict=True)
+
+
+def test_strict_allows_explicit_defaults():
+    """default() still fills in a value in strict mode"""
+    assert render_template("Hi {{ name|default('you') }}!", {}, strict=True) == "Hi you!"
+
+
+FRAGMENTS = {
+    "preamble": "You are a careful assistant.",
+    # trim_blocks drops the newline right after each tag
+    "base": "{% include 'preamble' %}\n\n{% block task %}{% endblock %}",
+}
+
+
+def test_include_fragment():
+    result = render_template('{% include "preamble" %} Make a {{ t|type_description }}.', {"t": Person}, fragments=FRAGMENTS)
+    assert result == "You are a careful assistant. Make a Person (A person)."
+
+
+def test_extend_fragment():
+    result = render_template('{% extends "base" %}{% block task %}Name: {{ name }}{% endblock %}', {"name": "Bo"}, fragments=FRAGMENTS)
+    assert result == "You are a careful assistant.\nName: Bo"
+
+
+def test_missing_fragment_raises():
+    with pytest.raises(jinja2.TemplateNotFound):
+        render_template('{% include "nope" %}', {})
+
+
+def test_file_template_includes_sibling(tmp_path):
+    (tmp_path / "header.j2").write_text("Header for {{ name }}\n")
+    (tmp_path / "main.j2").write_text('{% include "header.j2" %}{% include "preamble" %}')
+    result = render_file_template(str(tmp_path / "main.j2"), {"name": "Bo"}, fragments=FRAGMENTS)
+    assert result == "Header for Bo\nYou are a careful assistant."
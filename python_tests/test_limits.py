@@ -0,0 +1,74 @@
+#!/usr/bin/env python3
+"""
+Test depth and size limits against pathological streams
+"""
+
+from typing import List
+
+import pytest
+
+from gasp import Parser, Deserializable
+
+
+class Node(Deserializable):
+    label: str
+    children: List["Node"]
+
+
+class Note(Deserializable):
+    text: str
+
+
+def test_no_limits_by_default():
+    parser = Parser(Note)
+    parser.feed("<Note><text>" + "x" * 100_000 + "</text></Note>")
+    assert len(parser.validate().text) == 100_000
+
+
+def test_max_depth():
+    parser = Parser(List[List[List[int]]], max_depth=4)
+    with pytest.raises(ValueError, match="Limit exceeded: nesting depth 5 > max_depth 4"):
+        parser.feed("<list><item><item><item><item>1")
+
+
+def test_max_string_length_across_chunks():
+    parser = Parser(Note, max_string_length=10)
+    parser.feed("<Note><text>hello ")
+    with pytest.raises(ValueError, match="max_string_length 10"):
+        parser.feed("world!")
+
+
+def test_max_nodes():
+    parser = Parser(List[int], max_nodes=3)
+    with pytest.raises(ValueError, match="4 tags > max_nodes 3"):
+        parser.feed("<list><item>1</item><item>2</item><item>3</item></list>")
+
+
+def test_max_nodes_with_primitive_root():
+    """Tags inside a primitive root are counted like any others"""
+    parser = Parser(str, max_nodes=2)
+    with pytest.raises(ValueError, match="3 tags > max_nodes 2"):
+        parser.feed("<str>a<b>1</b><b>2</b></str>")
+
+
+def test_max_buffer_bytes_for_unterminated_tag():
+    """A '<' that never closes can't grow the buffer without bound"""
+    parser = Parser(Note, max_buffer_bytes=64)
+    parser.feed("<Note><text>ok</text>")
+    with pytest.raises(ValueError, match="max_buffer_bytes 64"):
+        parser.feed("<" + "a" * 100)
+
+
+def test_error_is_sticky():
+    """Once a limit trips, later chunks fail the same way"""
+    parser = Parser(Note, max_string_length=3)
+    with pytest.raises(ValueError):
+        parser.feed("<Note><text>toolong")
+    with pytest.raises(ValueError, match="Limit exceeded"):
+        parser.feed("</text></Note>")
+
+
+def test_within_limits():
+    parser = Parser(Note, max_depth=2, max_string_length=5, max_nodes=2, max_buffer_bytes=16)
+    parser.feed("<Note><text>hello</text></Note>")
+    assert parser.validate().text == "hello"
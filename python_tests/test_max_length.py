@@ -0,0 +1,70 @@
+"""Tests for MaxLength constraints on string fields."""
+
+from typing import Annotated, List
+
+import pytest
+
+from gasp import Deserializable, MaxLength, Parser
+from gasp.template_helpers import type_to_format_instructions
+
+
+class Tweet(Deserializable):
+    text: Annotated[str, MaxLength(10, on_overflow="truncate")]
+    handle: Annotated[str, MaxLength(4)]
+
+
+class Timeline(Deserializable):
+    tweets: List[Tweet]
+
+
+def test_truncate_policy():
+    parser = Parser(Tweet)
+    result = parser.feed("<Tweet><text>naïve café opening</text><handle>bob</handle></Tweet>")
+    assert result.text == "naïve café"
+    assert result.handle == "bob"
+    assert parser.truncated_paths() == [("Tweet.text", 18)]
+
+
+def test_partial_values_are_cut():
+    """Snapshots never show more of a truncated field than the final value"""
+    parser = Parser(Tweet)
+    assert parser.feed("<Tweet><text>a rather long").text == "a rather l"
+    assert parser.truncated_paths() == []
+    parser.feed(" one</text>")
+    assert parser.truncated_paths() == [("Tweet.text", 17)]
+
+
+def test_partial_primitive_root_is_cut():
+    parser = Parser(Annotated[str, MaxLength(3, on_overflow="truncate")])
+    assert parser.feed("<str>abcdef") == "abc"
+    assert parser.feed("g</str>") == "abc"
+    assert parser.truncated_paths() == [("str", 7)]
+
+
+def test_error_policy():
+    parser = Parser(Tweet)
+    with pytest.raises(ValueError, match=r"<handle> is 5 characters, over max_length 4"):
+        parser.feed("<Tweet><handle>alice</handle>")
+
+
+def test_error_policy_with_on_error_skip():
+    parser = Parser(Timeline, on_error="skip")
+    result = parser.feed(
+        "<Timeline><tweets>"
+        "<item><handle>alice</handle></item>"
+        "<item><handle>bob</handle></item>"
+        "</tweets></Timeline>"
+    )
+    assert [t.handle for t in result.tweets] == ["bob"]
+    assert [path for path, _, _ in parser.issues()] == ["Timeline.tweets[0]"]
+
+
+def test_limit_in_format_instructions():
+    instructions = type_to_format_instructions(Tweet)
+    assert '<text type="str">example string</text> (max 10 characters)' in instructions
+    assert "(max 4 characters)" in instructions
+
+
+def test_invalid_constraint():
+    with pytest.raises(ValueError, match="on_overflow"):
+        MaxLength(10, on_overflow="wrap")
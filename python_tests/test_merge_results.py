@@ -0,0 +1,101 @@
+"""Tests for merging a truncated result with a retried generation."""
+
+from typing import Dict, List, Optional
+
+import pytest
+
+from gasp import Deserializable, Parser, merge_results
+
+
+class Person(Deserializable):
+    name: str
+    email: Optional[str]
+    hobbies: List[str]
+
+
+class Team(Deserializable):
+    lead: Person
+    scores: Dict[str, int]
+
+
+def parse(schema, text):
+    parser = Parser(schema)
+    parser.feed(text)
+    return parser.finish()
+
+
+def test_fills_missing_fields_and_extends_lists():
+    first = parse(Person, "<Person><name>Alice</name><hobbies><item>chess</item><item>go")
+    second = parse(
+        Person,
+        "<Person><name>Alice</name><email>a@example.com</email>"
+        "<hobbies><item>chess</item><item>golf</item><item>tennis</item></hobbies></Person>",
+    )
+
+    merged, report = merge_results(first, second, Person)
+    assert merged.name == "Alice"
+    assert merged.email == "a@example.com"
+    assert merged.hobbies == ["chess", "golf", "tennis"]
+    assert report == {
+        "filled": ["Person.email"],
+        "extended": ["Person.hobbies[1]", "Person.hobbies"],
+        "conflicts": [],
+    }
+    # Inputs are left alone
+    assert first.email is None and first.hobbies == ["chess", "go"]
+
+
+def test_nested_objects_and_dicts():
+    first = parse(Team, '<Team><lead><name>Bo</name></lead><scores><item key="a">1</item>')
+    second = parse(
+        Team,
+        '<Team><lead><name>Bo</name><email>bo@example.com</email></lead>'
+        '<scores><item key="a">1</item><item key="b">2</item></scores></Team>',
+    )
+    merged, report = merge_results(first, second, Team)
+    assert merged.lead.email == "bo@example.com"
+    assert merged.scores == {"a": 1, "b": 2}
+    assert report["filled"] == ["Team.lead.email", "Team.scores[b]"]
+
+
+def test_conflict_policies():
+    first = parse(Person, "<Person><name>Alice</name>")
+    second = parse(Person, "<Person><name>Alicia Keys</name></Person>")
+    assert merge_results(first, second, Person)[0].name == "Alice"
+    assert merge_results(first, second, Person, conflict="second")[0].name == "Alicia Keys"
+
+    merged, report = merge_results(first, second, Person)
+    assert report["conflicts"] == ["Person.name"]
+    with pytest.raises(ValueError, match="Conflicting values at Person.name"):
+        merge_results(first, second, Person, conflict="error")
+    with pytest.raises(ValueError, match="Unknown conflict policy"):
+        merge_results(first, second, Person, conflict="newest")
+
+
+def test_missing_first_attempt():
+    second = parse(Person, "<Person><name>Cy</name></Person>")
+    merged, report = merge_results(None, second, Person)
+    assert merged is second
+    assert report["filled"] == ["Person"]
+
+
+class Reading(Deserializable):
+    value: float
+    unit: Optional[str]
+
+
+def test_epsilon_ignores_float_drift():
+    first = parse(Reading, "<Reading><value>0.30000000000000004</value>")
+    second = parse(Reading, "<Reading><value>0.3</value><unit>V</unit></Reading>")
+
+    merged, report = merge_results(first, second, Reading)
+    assert report["conflicts"] == ["Reading.value"]
+
+    merged, report = merge_results(first, second, Reading, epsilon=1e-9)
+    assert report == {"filled": ["Reading.unit"], "extended": [], "conflicts": []}
+    assert merged.value == first.value
+
+    merged, report = merge_results(first, second, Reading, epsilon=1e-20)
+    assert report["conflicts"] == ["Reading.value"]
+    with pytest.raises(ValueError, match="epsilon"):
+        merge_results(first, second, Reading, epsilon=-1)
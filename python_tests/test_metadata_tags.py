@@ -0,0 +1,51 @@
+#!/usr/bin/env python3
+"""
+Test metadata tags captured ahead of the main payload
+"""
+
+import json
+
+from gasp import Parser, Deserializable
+
+
+class Answer(Deserializable):
+    text: str
+
+
+def test_metadata_available_before_payload():
+    """Metadata is surfaced as soon as its tag closes"""
+    parser = Parser(Answer, metadata_tags=["Meta"])
+
+    parser.feed('<Meta>{"model": "x"}</Meta> Some prose <Ans')
+    assert json.loads(parser.get_metadata()["Meta"]) == {"model": "x"}
+    assert not parser.is_complete()
+
+    result = parser.feed("wer><text>hello</text></Answer>")
+    assert result.text == "hello"
+    assert parser.is_complete()
+
+
+def test_metadata_does_not_leak_into_payload():
+    """Metadata tags are never parsed as the target type"""
+    parser = Parser(str, metadata_tags=["Meta"])
+
+    result = parser.feed("<meta>first</meta><str>value</str>")
+    assert result == "value"
+    assert parser.get_metadata() == {"Meta": "first"}
+
+
+def test_metadata_split_across_chunks():
+    """Metadata text is accumulated across chunks"""
+    parser = Parser(Answer, metadata_tags=["Meta", "Usage"])
+
+    for chunk in ["<Me", "ta>par", "tial", "</Meta><Usage>12</Usage>"]:
+        parser.feed(chunk)
+
+    assert parser.get_metadata() == {"Meta": "partial", "Usage": "12"}
+
+
+def test_no_metadata_by_default():
+    """Parsers without metadata tags report an empty mapping"""
+    parser = Parser(Answer)
+    parser.feed("<Meta>ignored</Meta><Answer><text>hi</text></Answer>")
+    assert parser.get_metadata() == {}
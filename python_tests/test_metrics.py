@@ -0,0 +1,63 @@
+#!/usr/bin/env python3
+"""
+Test parser metrics (only available when built with the `metrics` feature)
+"""
+
+import pytest
+
+from gasp import Parser, Deserializable
+
+
+class Answer(Deserializable):
+    text: str
+
+
+def _require_metrics():
+    if not hasattr(Parser, "get_metrics"):
+        pytest.skip("gasp built without the metrics feature")
+
+
+def test_metrics_totals():
+    """Totals count every chunk, byte and tag event"""
+    _require_metrics()
+    parser = Parser(Answer)
+    parser.feed("<Answer><text>hi")
+    parser.feed("</text></Answer>")
+
+    metrics = parser.get_metrics()
+    assert metrics["total_chunks"] == 2
+    assert metrics["total_bytes"] == len("<Answer><text>hi</text></Answer>")
+    assert metrics["total_events"] == 5
+    assert metrics["avg_step_latency"] >= 0
+    assert metrics["chunks_per_sec"] >= 0
+
+
+def test_metrics_recorder_receives_samples():
+    """A Python recorder is called once per chunk"""
+    _require_metrics()
+    samples = []
+    parser = Parser(Answer)
+    parser.set_metrics_recorder(lambda nbytes, events, latency: samples.append((nbytes, events)))
+    parser.feed("<Answer><text>hi")
+    parser.feed("</text></Answer>")
+    assert samples == [(16, 3), (16, 2)]
+
+
+def test_fake_clock_makes_latency_deterministic():
+    """Step timings come from the clock passed to set_clock"""
+    _require_metrics()
+    now = [100.0]
+
+    def clock():
+        now[0] += 0.25  # every reading advances a quarter second
+        return now[0]
+
+    samples = []
+    parser = Parser(Answer)
+    parser.set_clock(clock)
+    parser.set_metrics_recorder(lambda nbytes, events, latency: samples.append(latency))
+    parser.feed("<Answer><text>hi")
+    parser.feed("</text></Answer>")
+
+    assert samples == [0.25, 0.25]
+    assert parser.get_metrics()["avg_step_latency"] == 0.25
@@ -0,0 +1,69 @@
+#!/usr/bin/env python3
+"""
+Test collecting several root values from one stream
+"""
+
+from typing import Union
+
+from gasp import Parser, Deserializable
+
+
+class Action(Deserializable):
+    name: str
+
+
+class Note(Deserializable):
+    text: str
+
+
+STREAM = (
+    "<Action><name>search</name></Action> then "
+    "<Note><text>hmm</text></Note> and "
+    "<Action><name>answer</name></Action>"
+)
+
+
+def test_multiple_collects_every_value():
+    """Each closed root tag adds a labeled result"""
+    parser = Parser(Union[Action, Note], multiple=True)
+    parser.feed(STREAM)
+
+    results = parser.get_results()
+    assert [tag for tag, _ in results] == ["Action", "Note", "Action"]
+    assert results[0][1].name == "search"
+    assert results[1][1].text == "hmm"
+    assert results[2][1].name == "answer"
+    assert not parser.is_complete()
+
+
+def test_multiple_across_chunks():
+    """Values split across chunks are collected once complete"""
+    parser = Parser(Action, multiple=True)
+    for chunk in ["<Action><name>a</na", "me></Action><Act", "ion><name>b</name></Action>"]:
+        parser.feed(chunk)
+    assert [value.name for _, value in parser.get_results()] == ["a", "b"]
+
+
+def test_multiple_primitives():
+    """Primitive roots are collected too"""
+    parser = Parser(int, multiple=True)
+    parser.feed("<int>1</int><int>2</int>")
+    parser.feed("<int>3</int>")
+    assert [value for _, value in parser.get_results()] == [1, 2, 3]
+
+
+def test_finish_adds_truncated_value():
+    """A value still open at finish() is collected as well"""
+    parser = Parser(Action, multiple=True)
+    parser.feed("<Action><name>a</name></Action><Action><name>b")
+    parser.finish()
+    assert [value.name for _, value in parser.get_results()] == ["a", "b"]
+    assert parser.is_complete()
+
+
+def test_single_mode_stops_at_first():
+    """Without multiple, only the first value is parsed"""
+    parser = Parser(Action)
+    result = parser.feed(STREAM)
+    assert result.name == "search"
+    assert parser.get_results() == []
@@ -0,0 +1,68 @@
+#!/usr/bin/env python3
+"""
+Test lossless number parsing: big integers and decimal floats
+"""
+
+import math
+from decimal import Decimal
+from typing import List
+
+from gasp import Parser, Deserializable
+
+
+class Record(Deserializable):
+    id: int
+    price: float
+
+
+def test_big_integers_are_exact():
+    """Integers beyond 64 bits keep every digit"""
+    parser = Parser(Record)
+    result = parser.feed("<Record><id>123456789012345678901234567890</id><price>1.5</price></Record>")
+    assert result.id == 123456789012345678901234567890
+    assert result.price == 1.5
+
+
+def test_negative_big_integer():
+    parser = Parser(int)
+    assert parser.feed("<int>-98765432109876543210</int>") == -98765432109876543210
+
+
+def test_invalid_integer_is_none():
+    """Non-numeric text still yields None rather than an error"""
+    parser = Parser(Record)
+    result = parser.feed("<Record><id>12ab</id></Record>")
+    assert result.id is None
+
+
+def test_decimal_floats():
+    """decimal_floats keeps the exact digits of float fields"""
+    parser = Parser(Record, decimal_floats=True)
+    result = parser.feed("<Record><id>1</id><price>0.10000000000000000001</price></Record>")
+    assert isinstance(result.price, Decimal)
+    assert result.price == Decimal("0.10000000000000000001")
+    assert result.id == 1
+
+
+def test_non_finite_floats():
+    for text, check in [("NaN", math.isnan), ("Infinity", lambda v: v == math.inf), ("-Infinity", lambda v: v == -math.inf)]:
+        parser = Parser(Record)
+        assert check(parser.feed(f"<Record><id>1</id><price>{text}</price></Record>").price)
+
+
+def test_python_numeric_literals():
+    """python_numbers accepts hex/octal/binary and digit separators"""
+    parser = Parser(List[int], python_numbers=True)
+    result = parser.feed("<list><item>0x1F</item><item>0o17</item><item>0b101</item><item>1_000_000</item><item>12ab</item></list>")
+    assert result == [31, 15, 5, 1000000, None]
+
+    parser = Parser(Record, python_numbers=True)
+    result = parser.feed("<Record><id>7</id><price>1_000.5</price></Record>")
+    assert result.price == 1000.5
+
+
+def test_python_numeric_literals_off_by_default():
+    parser = Parser(Record)
+    result = parser.feed("<Record><id>0x1F</id><price>1_000.5</price></Record>")
+    assert result.id is None
+    assert result.price is None
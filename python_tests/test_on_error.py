@@ -0,0 +1,72 @@
+"""Tests for skipping or nulling out container elements that fail to build."""
+
+from typing import Dict, List
+
+import pytest
+
+from gasp import Parser
+
+
+class Transfer:
+    to: str
+    amount: int
+
+
+class Batch:
+    transfers: List[Transfer]
+
+
+BATCH = (
+    "<Batch><transfers>"
+    "<item><to>a</to></item>"
+    "<item><to>b</to><to>c</to><amount>5</amount></item>"
+    "<item><to>d</to></item>"
+    "</transfers></Batch>"
+)
+
+
+def test_raise_is_default():
+    parser = Parser(Batch, duplicate_keys="error")
+    with pytest.raises(ValueError, match="Duplicate field 'to'"):
+        parser.feed(BATCH)
+
+
+def test_skip_drops_bad_element():
+    parser = Parser(Batch, duplicate_keys="error", on_error="skip")
+    for i in range(0, len(BATCH), 5):
+        result = parser.feed(BATCH[i:i + 5])
+    assert [t.to for t in result.transfers] == ["a", "d"]
+    assert parser.is_complete()
+    assert parser.issues() == [
+        ("Batch.transfers[1]", BATCH.index("<to>c") + 5, "Duplicate field 'to' in <item>")
+    ]
+
+
+def test_null_keeps_position():
+    parser = Parser(Batch, duplicate_keys="error", on_error="null")
+    result = parser.feed(BATCH)
+    assert [t and t.to for t in result.transfers] == ["a", None, "d"]
+
+
+def test_failure_on_element_close():
+    for mode, expected in [("skip", [1, 3]), ("null", [1, None, 3])]:
+        parser = Parser(List[int], empty_as={"*": "error"}, on_error=mode)
+        assert parser.feed("<list><item>1</item><item> </item><item>3</item></list>") == expected
+        assert [path for path, _, _ in parser.issues()] == ["list[1]"]
+
+
+def test_dict_value():
+    parser = Parser(Dict[str, int], empty_as={"*": "error"}, on_error="null")
+    result = parser.feed('<dict><item key="a">1</item><item key="b"></item><item key="c">3</item></dict>')
+    assert result == {"a": 1, "b": None, "c": 3}
+
+
+def test_error_outside_elements_still_raises():
+    parser = Parser(Transfer, empty_as={"*": "error"}, on_error="skip")
+    with pytest.raises(ValueError, match="<to> is empty"):
+        parser.feed("<Transfer><to></to>")
+
+
+def test_unknown_policy():
+    with pytest.raises(ValueError, match="on_error"):
+        Parser(Batch, on_error="ignore")
@@ -0,0 +1,50 @@
+#!/usr/bin/env python3
+"""
+Test which Parser options need a type, and that from_pydantic takes them too
+"""
+
+import pytest
+
+from gasp import Deserializable, Parser
+
+
+class Step(Deserializable):
+    name: str
+    count: int
+
+
+def test_type_only_options_need_a_type():
+    with pytest.raises(ValueError, match="multiple can't be used without a type_obj"):
+        Parser(multiple=True)
+    with pytest.raises(ValueError, match="on_error, strict_types can't"):
+        Parser(on_error="skip", strict_types=True)
+    with pytest.raises(ValueError, match="duplicate_keys"):
+        Parser(duplicate_keys="collect")
+
+
+def test_untyped_options_are_accepted():
+    parser = Parser(metadata_tags=["Meta"], passthrough_text=True, max_nodes=10, trace_tags=True)
+    parser.feed("hello <Meta>m</Meta>")
+    assert parser.take_text() == "hello "
+
+
+def test_from_pydantic_takes_parser_options():
+    parser = Parser.from_pydantic(Step, multiple=True, strict_types=True)
+    parser.feed("<Step><name>a</name><count>1</count></Step><Step><name>b</name>")
+    parser.feed("<count>2</count></Step>")
+    assert [(tag, s.name, s.count) for tag, s in parser.get_results()] == [("Step", "a", 1), ("Step", "b", 2)]
+    with pytest.raises(ValueError, match="where int was declared"):
+        Parser.from_pydantic(Step, strict_types=True).feed("<Step><count>x</count>")
+
+
+def test_unknown_options_raise_type_error():
+    with pytest.raises(TypeError, match="Parser\\(\\) got an unexpected keyword argument 'strict'"):
+        Parser(Step, strict=True)
+    with pytest.raises(TypeError, match="from_pydantic\\(\\) got an unexpected keyword argument 'strict'"):
+        Parser.from_pydantic(Step, strict=True)
+
+
+def test_from_pydantic_ignores_no_tags_by_default():
+    result = Parser.from_pydantic(Step).feed("<think><Step><name>a</name></Step></think>")
+    assert result.name == "a"
+    assert Parser(Step).feed("<think><Step><name>a</name></Step></think>") is None
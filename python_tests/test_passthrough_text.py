@@ -0,0 +1,27 @@
+#!/usr/bin/env python3
+"""
+Test passthrough_text capturing prose outside the payload tags
+"""
+
+from gasp import Parser, Deserializable
+
+
+class Action(Deserializable):
+    name: str
+
+
+def test_take_text_returns_prose_around_payload():
+    parser = Parser(Action, passthrough_text=True)
+    parser.feed("Let me think. <think>hidden</think>I'll search")
+    assert parser.take_text() == "Let me think. I'll search"
+
+    result = parser.feed(" now.<Action><name>search</name></Action> Done.")
+    assert result.name == "search"
+    assert parser.take_text() == " now. Done."
+    assert parser.take_text() == ""
+
+
+def test_text_is_dropped_by_default():
+    parser = Parser(Action)
+    parser.feed("prose <Action><name>x</name></Action>")
+    assert parser.take_text() == ""
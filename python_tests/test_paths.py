@@ -0,0 +1,81 @@
+"""Tests for get_path, pointer and their setters in gasp.paths."""
+
+from typing import Dict, List
+
+import pytest
+
+from gasp import Deserializable, Parser, get_path, pointer, set_path, set_pointer
+from gasp.paths import parse_path, parse_pointer
+
+
+class Address(Deserializable):
+    city: str
+
+
+class Person(Deserializable):
+    name: str
+    address: Address
+    tags: List[str]
+    extra: Dict[str, str]
+
+
+def parse_person():
+    parser = Parser(Person)
+    parser.feed(
+        "<Person><name>Ada</name><address><city>London</city></address>"
+        '<tags type="list"><item>a</item><item>b</item></tags>'
+        '<extra type="dict"><item key="a/b">slash</item></extra></Person>'
+    )
+    return parser.finish()
+
+
+def test_parse_path_and_pointer():
+    assert parse_path("items[2].name") == ["items", 2, "name"]
+    assert parse_path('extra["a.b"]') == ["extra", "a.b"]
+    assert parse_pointer("/extra/a~1b/~0x") == ["extra", "a/b", "~x"]
+    assert parse_pointer("") == []
+    with pytest.raises(ValueError, match="at offset 5"):
+        parse_path("name..city")
+    with pytest.raises(ValueError, match="must be empty or start with '/'"):
+        parse_pointer("name")
+
+
+def test_get_path_on_parsed_result():
+    person = parse_person()
+    assert get_path(person, "address.city") == "London"
+    assert get_path(person, "tags[1]") == "b"
+    # Paths from reports lead with the root type's name
+    assert get_path(person, "Person.address.city") == "London"
+    assert get_path(person, 'extra["a/b"]') == "slash"
+
+
+def test_pointer_on_parsed_result():
+    person = parse_person()
+    assert pointer(person, "/address/city") == "London"
+    assert pointer(person, "/tags/0") == "a"
+    assert pointer(person, "/extra/a~1b") == "slash"
+    assert pointer(person, "") is person
+
+
+def test_missing_paths():
+    person = parse_person()
+    assert get_path(person, "tags[5]", default=None) is None
+    assert pointer(person, "/address/zip", default="?") == "?"
+    with pytest.raises(KeyError, match="nothing at address.zip"):
+        get_path(person, "address.zip.code")
+    with pytest.raises(KeyError, match=r"nothing at tags\[x\]|nothing at tags.x"):
+        pointer(person, "/tags/x")
+
+
+def test_setters_modify_in_place():
+    person = parse_person()
+    set_path(person, "address.city", "Paris")
+    set_pointer(person, "/tags/1", "z")
+    set_path(person, "extra.new", "value")
+    assert person.address.city == "Paris"
+    assert person.tags == ["a", "z"]
+    assert person.extra["new"] == "value"
+    with pytest.raises(KeyError):
+        set_pointer(person, "/tags/9", "nope")
+    with pytest.raises(ValueError, match="nothing to assign to"):
+        set_pointer(person, "", "nope")
@@ -0,0 +1,63 @@
+"""Tests for python_literals: None and Python-style container text."""
+
+from typing import List, Optional, Set, Tuple
+
+import pytest
+
+from gasp import Deserializable, Parser
+
+
+class Config(Deserializable):
+    scores: List[int]
+    pair: Tuple[int, int]
+    tags: Set[str]
+    note: Optional[str]
+    enabled: bool
+
+
+TEXT = (
+    "<Config><scores>(1, 2, 3)</scores><pair>(4, 5)</pair>"
+    "<tags>{'a', 'b'}</tags><note>None</note><enabled>True</enabled></Config>"
+)
+
+
+def test_literals_off_by_default():
+    result = Parser(Config).feed(TEXT)
+    assert result.scores == []
+    assert result.note == "None"
+    assert result.enabled is True
+
+
+def test_python_literals():
+    parser = Parser(Config, python_literals=True)
+    for i in range(0, len(TEXT), 4):
+        result = parser.feed(TEXT[i:i + 4])
+    assert result.scores == [1, 2, 3]
+    assert result.pair == (4, 5)
+    assert result.tags == {"a", "b"}
+    assert result.note is None
+    assert result.enabled is True
+
+
+def test_item_tags_still_win():
+    parser = Parser(List[List[int]], python_literals=True)
+    result = parser.feed("<list><item>[1, 2]</item><item><item>3</item></item></list>")
+    assert result == [[1, 2], [3]]
+
+
+def test_non_literal_text_is_ignored():
+    parser = Parser(List[str], python_literals=True)
+    assert parser.feed("<list>(a, b)</list>") == []
+
+
+def test_literal_items_are_converted_to_item_type():
+    parser = Parser(List[int], python_literals=True)
+    assert parser.feed("<list>['1', 2]</list>") == [1, 2]
+    parser = Parser(Tuple[int, str], python_literals=True)
+    assert parser.feed("<tuple>('3', 4)</tuple>") == (3, "4")
+
+
+def test_mismatching_literal_item_raises_with_strict_types():
+    parser = Parser(List[int], python_literals=True, strict_types=True)
+    with pytest.raises(ValueError, match='received "x" where int was declared'):
+        parser.feed("<list>['x', 2]</list>")
@@ -0,0 +1,41 @@
+"""Tests for Parser.reset, which reuses a parser for the next stream."""
+
+from typing import List
+
+from gasp import Deserializable, Parser
+
+
+class Answer(Deserializable):
+    text: str
+    score: int
+
+
+def test_reset_mid_stream():
+    parser = Parser(Answer)
+    parser.feed("<Answer><text>first, never fini")
+    parser.reset()
+    assert parser.get_partial() is None
+    assert parser.buffered_bytes() == 0
+
+    result = parser.feed("<Answer><text>second</text><score>2</score></Answer>")
+    assert (result.text, result.score) == ("second", 2)
+    assert parser.is_complete()
+
+
+def test_reset_after_complete_keeps_options():
+    parser = Parser(List[int], on_error="skip", python_numbers=True)
+    assert parser.feed("<list><item>0x10</item></list>") == [16]
+    parser.reset()
+    assert not parser.is_complete()
+    assert parser.stats()["bytes"] == 0
+    assert parser.feed("<list><item>0b11</item><item>1</item></list>") == [3, 1]
+
+
+def test_reset_keeps_subscriptions():
+    seen = []
+    parser = Parser(Answer)
+    parser.subscribe("Answer.score", lambda path, value: seen.append(value))
+    parser.feed("<Answer><score>1</score></Answer>")
+    parser.reset()
+    parser.feed("<Answer><score>2</score></Answer>")
+    assert seen == [1, 2]
@@ -0,0 +1,47 @@
+"""Tests for SnapshotWatch, which shares the latest snapshot across threads."""
+
+import threading
+
+from gasp import Deserializable, Parser, SnapshotWatch
+
+
+class Answer(Deserializable):
+    text: str
+
+
+def test_latest_before_and_after_feed():
+    watch = SnapshotWatch(Parser(Answer))
+    assert watch.latest() == (0, None, False)
+    watch.feed("<Answer><text>hi")
+    version, answer, done = watch.latest()
+    assert version == 1 and answer.text == "hi" and not done
+
+
+def test_wait_times_out_without_news():
+    watch = SnapshotWatch(Parser(Answer))
+    watch.feed("<Answer><text>a")
+    assert watch.wait(after=1, timeout=0.01)[0] == 1
+
+
+def test_reader_thread_sees_final_value():
+    watch = SnapshotWatch(Parser(Answer))
+    seen = []
+
+    def reader():
+        version, answer, done = watch.wait()
+        while not done:
+            seen.append(answer.text)
+            version, answer, done = watch.wait(version, timeout=5)
+        seen.append(("final", answer.text))
+
+    thread = threading.Thread(target=reader)
+    thread.start()
+    for chunk in ["<Answer><text>he", "llo", "</text></Answer>"]:
+        watch.feed(chunk)
+    watch.finish()
+    thread.join(timeout=5)
+
+    assert not thread.is_alive()
+    assert seen[-1] == ("final", "hello")
+    # The reader may skip snapshots, but never sees them out of order
+    assert seen[:-1] == sorted(seen[:-1], key=len)
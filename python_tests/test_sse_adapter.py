@@ -0,0 +1,79 @@
+#!/usr/bin/env python3
+"""
+Test feeding a Parser from Server-Sent Events response bodies
+"""
+
+import json
+
+from gasp import Parser, Deserializable, SseAdapter
+
+
+class Person(Deserializable):
+    name: str
+    age: int
+
+
+PAYLOAD = "<Person><name>Alice</name><age>30</age></Person>"
+
+
+def _openai_body(text):
+    body = b": keep-alive\n\n"
+    for i in range(0, len(text), 7):
+        event = {"choices": [{"index": 0, "delta": {"content": text[i:i + 7]}}]}
+        body += b"data: " + json.dumps(event).encode() + b"\r\n\r\n"
+    return body + b"data: [DONE]\n\n"
+
+
+def _anthropic_body(text):
+    body = b"event: message_start\ndata: " + json.dumps({"type": "message_start"}).encode() + b"\n\n"
+    for i in range(0, len(text), 5):
+        event = {"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": text[i:i + 5]}}
+        body += b"event: content_block_delta\ndata: " + json.dumps(event).encode() + b"\n\n"
+    return body + b"event: message_stop\ndata: " + json.dumps({"type": "message_stop"}).encode() + b"\n\n"
+
+
+def test_openai_events_split_at_every_byte():
+    """Events split at arbitrary byte boundaries are reassembled"""
+    body = _openai_body(PAYLOAD)
+    adapter = SseAdapter(Parser(Person))
+    for i in range(len(body)):
+        adapter.feed(body[i:i + 1])
+
+    assert adapter.done
+    person = adapter.parser.validate()
+    assert person.name == "Alice"
+    assert person.age == 30
+
+
+def test_anthropic_text_deltas():
+    """Only text_delta events contribute text"""
+    adapter = SseAdapter(Parser(Person))
+    result = adapter.feed(_anthropic_body(PAYLOAD))
+
+    assert adapter.done
+    assert isinstance(result, Person)
+    assert result.name == "Alice"
+
+
+def test_multibyte_text_split_across_chunks():
+    """A UTF-8 character split between chunks is decoded intact"""
+    event = {"choices": [{"delta": {"content": "<Person><name>Zoë</name><age>1</age></Person>"}}]}
+    body = ("data: " + json.dumps(event, ensure_ascii=False) + "\n\n").encode()
+    split = body.index("ë".encode()) + 1
+
+    adapter = SseAdapter(Parser(Person))
+    adapter.feed(body[:split])
+    adapter.feed(body[split:])
+    assert adapter.parser.validate().name == "Zoë"
+
+
+def test_events_without_text_are_skipped():
+    """Role-only deltas, unknown events and bad JSON feed nothing"""
+    adapter = SseAdapter(Parser(Person))
+    body = (
+        b'data: {"choices": [{"delta": {"role": "assistant"}}]}\n\n'
+        b'data: {"type": "ping"}\n\n'
+        b"data: not json\n\n"
+    )
+    assert adapter.feed(body) is None
+    assert not adapter.done
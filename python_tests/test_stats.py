@@ -0,0 +1,45 @@
+#!/usr/bin/env python3
+"""
+Test the always-on parser counters
+"""
+
+from typing import List
+
+from gasp import Parser, Deserializable
+
+
+class Step(Deserializable):
+    action: str
+
+
+class Plan(Deserializable):
+    steps: List[Step]
+
+
+def test_stats_start_at_zero():
+    assert Parser(Plan).stats() == {"bytes": 0, "events": 0, "snapshots": 0, "peak_depth": 0}
+
+
+def test_stats_count_bytes_events_and_depth():
+    chunks = ["<Plan><steps><item><Step><action>go", "</action></Step></item></steps></Plan>"]
+    parser = Parser(Plan)
+    for chunk in chunks:
+        parser.feed(chunk)
+
+    stats = parser.stats()
+    assert stats["bytes"] == sum(len(c) for c in chunks)
+    # 5 opens, 1 text, 5 closes
+    assert stats["events"] == 11
+    # Plan > steps > Step > action; <item> wrappers don't open a frame
+    assert stats["peak_depth"] == 4
+    # Only the first chunk left the value incomplete
+    assert stats["snapshots"] == 1
+
+
+def test_stats_for_primitive_root():
+    parser = Parser(int)
+    parser.feed("<int>4")
+    parser.feed("2</int>")
+    stats = parser.stats()
+    assert stats["peak_depth"] == 1
+    assert stats["snapshots"] == 1
@@ -0,0 +1,68 @@
+#!/usr/bin/env python3
+"""
+Test helpers that drive a Parser from a chunk stream
+"""
+
+import asyncio
+
+from gasp import Parser, Deserializable, parse_async_stream, parse_stream
+
+
+class Person(Deserializable):
+    name: str
+    age: int
+
+
+CHUNKS = ["<Person><na", "me>Alice</name>", "<age>30</age>", "</Person>", "trailing text"]
+
+
+async def _agen(chunks):
+    for chunk in chunks:
+        await asyncio.sleep(0)
+        yield chunk
+
+
+def test_parse_async_stream_yields_partials():
+    """Each chunk that produces a value yields the current object"""
+
+    async def collect():
+        return [p async for p in parse_async_stream(Parser(Person), _agen(CHUNKS))]
+
+    results = asyncio.run(collect())
+    assert len(results) >= 2
+    final = results[-1]
+    assert isinstance(final, Person)
+    assert final.name == "Alice"
+    assert final.age == 30
+
+
+def test_parse_async_stream_stops_when_complete():
+    """Chunks after completion are not consumed"""
+    consumed = []
+
+    async def tracking():
+        for chunk in CHUNKS:
+            consumed.append(chunk)
+            yield chunk
+
+    async def drain():
+        async for _ in parse_async_stream(Parser(Person), tracking()):
+            pass
+
+    asyncio.run(drain())
+    assert "trailing text" not in consumed
+
+
+def test_parse_stream_yields_partials():
+    """The sync helper yields the same sequence as the async one"""
+    results = list(parse_stream(Parser(Person), CHUNKS))
+    assert results[-1].name == "Alice"
+    assert results[-1].age == 30
+
+
+def test_parse_stream_stops_when_complete():
+    """The chunk iterator is not advanced past completion"""
+    chunks = iter(CHUNKS)
+    for _ in parse_stream(Parser(Person), chunks):
+        pass
+    assert next(chunks) == "trailing text"
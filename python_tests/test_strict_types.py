@@ -0,0 +1,82 @@
+"""Tests for strict_types, which raises on number and bool fields that can't match their type."""
+
+from typing import List, Optional
+
+import pytest
+
+from gasp import Deserializable, Parser
+
+
+class Person(Deserializable):
+    name: str
+    age: int
+    height: Optional[float] = None
+    active: bool = False
+
+
+class Team(Deserializable):
+    people: List[Person]
+
+
+def test_off_by_default():
+    result = Parser(Person).feed("<Person><age>abc</age></Person>")
+    assert result.age is None
+
+
+def test_raises_mid_field():
+    parser = Parser(Person, strict_types=True)
+    parser.feed("<Person><name>Al</name><age>4")
+    with pytest.raises(ValueError, match=r'<age> received "4x" where int was declared at line 1'):
+        parser.feed("x")
+
+
+def test_raises_when_field_closes():
+    # "1e" is a prefix of a float but not a whole one
+    parser = Parser(Person, strict_types=True)
+    with pytest.raises(ValueError, match=r'<height> received "1e" where float was declared'):
+        parser.feed("<Person><height>1e</height>")
+
+
+def test_valid_values_pass():
+    parser = Parser(Person, strict_types=True)
+    text = "<Person><name>Al</name><age>-42</age><height>1.5e0</height><active>yes</active></Person>"
+    for i in range(0, len(text), 3):
+        result = parser.feed(text[i:i + 3])
+    assert (result.age, result.height, result.active) == (-42, 1.5, True)
+
+
+def test_special_floats_and_python_numbers():
+    parser = Parser(Person, strict_types=True, python_numbers=True)
+    result = parser.feed("<Person><age>0x1F</age><height>Infinity</height></Person>")
+    assert result.age == 31
+    assert result.height == float("inf")
+
+
+def test_bad_bool():
+    with pytest.raises(ValueError, match=r'<active> received "maybe" where bool was declared'):
+        Parser(Person, strict_types=True).feed("<Person><active>maybe</active>")
+
+
+def test_primitive_root():
+    with pytest.raises(ValueError, match=r'<int> received "12a" where int was declared'):
+        Parser(int, strict_types=True).feed("<int>12a")
+
+
+def test_with_on_error_skip():
+    parser = Parser(Team, strict_types=True, on_error="skip")
+    result = parser.feed(
+        "<Team><people>"
+        "<item><Person><age>old</age></Person></item>"
+        "<item><Person><age>30</age></Person></item>"
+        "</people></Team>"
+    )
+    assert [p.age for p in result.people] == [30]
+
+
+def test_padded_values_pass():
+    text = "<Person><name>Al</name><age> 42 </age><height>\n1.5 </height><active> true</active></Person>"
+    parser = Parser(Person, strict_types=True)
+    for i in range(0, len(text), 2):
+        result = parser.feed(text[i:i + 2])
+    assert (result.age, result.height, result.active) == (42, 1.5, True)
+    assert Parser(Person).feed(text).age == 42
@@ -0,0 +1,52 @@
+#!/usr/bin/env python3
+"""
+Test take_deltas() reporting text appended to fields as it streams
+"""
+
+from typing import Dict, List
+
+from gasp import Parser, Deserializable
+
+
+class Answer(Deserializable):
+    title: str
+    steps: List[str]
+    notes: Dict[str, str]
+
+
+def test_deltas_report_appended_text_with_paths():
+    """Each chunk's new text is reported once, with the field it belongs to"""
+    parser = Parser(Answer, string_deltas=True)
+
+    parser.feed("<Answer><title>Hel")
+    assert parser.take_deltas() == [("Answer.title", "Hel")]
+
+    parser.feed("lo</title><steps><item>one</item><item>tw")
+    assert parser.take_deltas() == [
+        ("Answer.title", "lo"),
+        ("Answer.steps[0]", "one"),
+        ("Answer.steps[1]", "tw"),
+    ]
+
+    parser.feed('o</item></steps><notes><item key="a">x</item></notes></Answer>')
+    assert parser.take_deltas() == [
+        ("Answer.steps[1]", "o"),
+        ("Answer.notes[a]", "x"),
+    ]
+    assert parser.take_deltas() == []
+    assert parser.is_complete()
+
+
+def test_deltas_primitive_root():
+    """Primitive roots report deltas under their tag name"""
+    parser = Parser(str, string_deltas=True)
+    parser.feed("<str>ab")
+    parser.feed("cd</str>")
+    assert parser.take_deltas() == [("str", "ab"), ("str", "cd")]
+
+
+def test_deltas_off_by_default():
+    """Nothing is recorded unless string_deltas is set"""
+    parser = Parser(Answer)
+    parser.feed("<Answer><title>Hello</title></Answer>")
+    assert parser.take_deltas() == []
@@ -0,0 +1,71 @@
+#!/usr/bin/env python3
+"""
+Test subscribe() callbacks for values at specific paths
+"""
+
+from typing import List
+
+from gasp import Parser, Deserializable
+
+
+class Arguments(Deserializable):
+    query: str
+    limit: int
+
+
+class ToolCall(Deserializable):
+    name: str
+    arguments: Arguments
+    tags: List[str]
+
+
+def test_subscribe_fires_only_for_matching_paths():
+    """Callbacks run once per completed value at their path"""
+    seen = []
+    parser = Parser(ToolCall)
+    parser.subscribe("ToolCall.arguments.query", lambda path, value: seen.append((path, value)))
+    parser.subscribe("ToolCall.tags[1]", lambda path, value: seen.append((path, value)))
+
+    parser.feed("<ToolCall><name>search</name><arguments><query>ca")
+    assert seen == []
+
+    parser.feed("ts</query><limit>5</limit></arguments>")
+    assert seen == [("ToolCall.arguments.query", "cats")]
+
+    parser.feed("<tags><item>a</item><item>b</item></tags></ToolCall>")
+    assert seen == [("ToolCall.arguments.query", "cats"), ("ToolCall.tags[1]", "b")]
+
+
+def test_subscribe_to_root_and_nested_object():
+    """Object values and the root itself can be subscribed to"""
+    seen = []
+    parser = Parser(ToolCall)
+    parser.subscribe("ToolCall.arguments", lambda path, value: seen.append(value))
+    parser.subscribe("ToolCall", lambda path, value: seen.append(value))
+
+    result = parser.feed(
+        "<ToolCall><name>x</name><arguments><query>q</query><limit>1</limit></arguments>"
+        "<tags></tags></ToolCall>"
+    )
+    assert isinstance(seen[0], Arguments)
+    assert seen[0].limit == 1
+    assert seen[1] is result
+
+
+def test_subscribe_on_finish():
+    """Values force-closed by finish() are reported too"""
+    seen = []
+    parser = Parser(ToolCall)
+    parser.subscribe("ToolCall.name", lambda path, value: seen.append(value))
+    parser.feed("<ToolCall><name>trunc")
+    parser.finish()
+    assert seen == ["trunc"]
+
+
+def test_subscribe_primitive_root():
+    """Primitive roots are reported under their tag name"""
+    seen = []
+    parser = Parser(int)
+    parser.subscribe("int", lambda path, value: seen.append(value))
+    parser.feed("<int>42</int>")
+    assert seen == [42]
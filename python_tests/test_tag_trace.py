@@ -0,0 +1,59 @@
+"""Tests for recording the tag finder's events with trace_tags."""
+
+import json
+
+from gasp import Parser
+
+
+class Answer:
+    value: int
+
+
+def test_trace_off_by_default():
+    parser = Parser(Answer)
+    parser.feed("<Answer><value>1</value></Answer>")
+    assert json.loads(parser.tag_trace()) == []
+    assert parser.format_tag_trace() == ""
+
+
+def test_trace_explains_skipped_tags():
+    parser = Parser(Answer, trace_tags=True)
+    parser.feed("<think>x</think><Note/>")
+    parser.feed("<Answer><value>42</value></Answer></Stray>")
+
+    trace = json.loads(parser.tag_trace())
+    skipped = [(e["name"], e["reason"]) for e in trace if e["kind"] == "skipped"]
+    assert skipped == [
+        ("think", "ignored"),
+        ("think", "ignored"),
+        ("Note", "not_wanted"),
+        ("Stray", "unmatched_close"),
+    ]
+    opens = [e for e in trace if e["kind"] == "open"]
+    assert [(e["name"], e["depth"]) for e in opens] == [("Answer", 1), ("value", 2)]
+
+
+def test_offsets_span_chunks():
+    text = "prefix <Answer><value>7</value></Answer>"
+    parser = Parser(Answer, trace_tags=True)
+    for i in range(0, len(text), 3):
+        parser.feed(text[i:i + 3])
+
+    trace = json.loads(parser.tag_trace())
+    open_answer = next(e for e in trace if e["kind"] == "open" and e["name"] == "Answer")
+    assert open_answer["offset"] == text.index("<Answer>")
+    close_value = next(e for e in trace if e["kind"] == "close" and e["name"] == "value")
+    assert close_value["offset"] == text.index("</value>")
+
+
+def test_format_tag_trace_indents_by_depth():
+    parser = Parser(Answer, trace_tags=True)
+    parser.feed("<Answer><value>42</value></Answer>")
+    lines = parser.format_tag_trace().splitlines()
+    assert lines == [
+        "     0  +Answer@1",
+        "     8    +value@2",
+        "    15      \"42\"",
+        "    17    -value@2",
+        "    25  -Answer@1",
+    ]
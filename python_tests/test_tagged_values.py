@@ -0,0 +1,53 @@
+#!/usr/bin/env python3
+"""
+Test feed_tagged() reporting which root tag produced a value
+"""
+
+from typing import Union
+
+from gasp import Parser, Deserializable
+
+
+class Search(Deserializable):
+    query: str
+
+
+class Answer(Deserializable):
+    text: str
+
+
+def test_feed_tagged_labels_partial_and_complete():
+    """The tag is reported while streaming and after completion"""
+    parser = Parser(Union[Search, Answer])
+
+    assert parser.feed_tagged("Let me look that up. ") is None
+
+    tag, value, complete = parser.feed_tagged("<Search><query>cats</query>")
+    assert tag == "Search"
+    assert isinstance(value, Search)
+    assert not complete
+
+    tag, value, complete = parser.feed_tagged("</Search>")
+    assert tag == "Search"
+    assert value.query == "cats"
+    assert complete
+
+
+def test_feed_tagged_with_multiple():
+    """In multiple mode the label follows the value being built"""
+    parser = Parser(Union[Search, Answer], multiple=True)
+    parser.feed_tagged("<Search><query>cats</query></Search>")
+
+    tag, value, complete = parser.feed_tagged("<Answer><text>meow")
+    assert tag == "Answer"
+    assert not complete
+
+    tag, value, complete = parser.feed_tagged("</text></Answer>")
+    assert (tag, value.text, complete) == ("Answer", "meow", True)
+
+
+def test_feed_tagged_primitive():
+    """Primitive roots are labeled with their tag"""
+    parser = Parser(int)
+    assert parser.feed_tagged("<int>4") == ("int", 4, False)
+    assert parser.feed_tagged("2</int>") == ("int", 42, True)
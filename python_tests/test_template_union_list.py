@@ -89,6 +89,27 @@ def test_nested_dict_in_list():
     assert '<key' not in instructions
 
 
+def test_union_list_shows_item_per_member():
+    """Each union member gets a correctly-shaped example item"""
+
+    instructions = type_to_format_instructions(List[Union[Chat, int]])
+
+    assert '<item type="Chat">\n        ...Chat fields...\n    </item>' in instructions
+    assert '<item type="int">42</item>' in instructions
+    assert "some type from" not in instructions
+
+
+def test_tuple_format_with_class_member():
+    """Fixed-length tuples show one item per position, with class structures"""
+
+    instructions = type_to_format_instructions(tuple[str, IssueForm])
+
+    assert 'type="tuple[str, IssueForm]"' in instructions
+    assert '<item type="str">example string</item>' in instructions
+    assert '...IssueForm fields...' in instructions
+    assert "When you see 'IssueForm' in a type attribute" in instructions
+
+
 # Define type alias using type statement
 type AgentAction = Chat | IssueForm | WaitForConfirmation
 
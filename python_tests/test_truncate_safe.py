@@ -0,0 +1,42 @@
+#!/usr/bin/env python3
+"""
+Test display truncation of partial strings
+"""
+
+from gasp import truncate_safe
+
+
+def test_short_text_unchanged():
+    assert truncate_safe("hello", 5) == "hello"
+    assert truncate_safe("", 0) == ""
+
+
+def test_cut_appends_ellipsis_within_limit():
+    result = truncate_safe("hello world", 8)
+    assert result == "hello w…"
+    assert len(result) == 8
+
+
+def test_combining_marks_stay_with_base():
+    # "e" + combining acute accent must not be split
+    text = "cafe\u0301 au lait"
+    assert truncate_safe(text, 5) == "caf…"
+    assert truncate_safe(text, 6) == "cafe\u0301…"
+
+
+def test_zwj_emoji_sequence_not_broken():
+    family = "\U0001F468‍\U0001F469‍\U0001F467"
+    text = "hi " + family + " there"
+    for limit in range(4, 3 + len(family) + 1):
+        result = truncate_safe(text, limit)
+        assert result == "hi …", (limit, result)
+
+
+def test_skin_tone_modifier_kept():
+    text = "ok \U0001F44D\U0001F3FD done"
+    assert truncate_safe(text, 5) == "ok …"
+
+
+def test_custom_ellipsis():
+    assert truncate_safe("abcdefgh", 6, ellipsis="...") == "abc..."
+    assert truncate_safe("abcdefgh", 2, ellipsis="...") == ".."
@@ -0,0 +1,67 @@
+"""Tests for picking the member of a nested union when the tag has no type attribute."""
+
+from typing import List, Union
+
+import pytest
+
+from gasp import Deserializable, Parser
+
+
+class Cat(Deserializable):
+    meows: int
+
+
+class Dog(Deserializable):
+    barks: int
+
+
+class Owner(Deserializable):
+    name: str
+    pet: Union[Cat, Dog]
+
+
+def parse_pet(pet_xml, chunk_size=None):
+    text = f"<Owner><name>Ann</name><pet>{pet_xml}</pet></Owner>"
+    parser = Parser(Owner)
+    step = chunk_size or len(text)
+    for i in range(0, len(text), step):
+        result = parser.feed(text[i:i + step])
+    return result
+
+
+def test_type_attribute_still_wins():
+    parser = Parser(Owner)
+    result = parser.feed('<Owner><pet type="Cat"><meows>2</meows></pet></Owner>')
+    assert isinstance(result.pet, Cat)
+
+
+def test_member_tag():
+    result = parse_pet("<Dog><barks>3</barks></Dog>")
+    assert isinstance(result.pet, Dog)
+    assert result.pet.barks == 3
+    assert result.name == "Ann"
+
+
+def test_structural_match():
+    result = parse_pet("<meows>5</meows>")
+    assert isinstance(result.pet, Cat)
+    assert result.pet.meows == 5
+
+
+def test_type_key():
+    result = parse_pet("<_type>Dog</_type><barks>1</barks>", chunk_size=4)
+    assert isinstance(result.pet, Dog)
+    assert result.pet.barks == 1
+
+
+def test_unknown_type_key():
+    with pytest.raises(ValueError, match="not one of Cat, Dog"):
+        parse_pet("<_type>Fish</_type>")
+
+
+def test_list_items():
+    parser = Parser(List[Union[Cat, Dog]])
+    result = parser.feed(
+        "<list><item><barks>3</barks></item><item><Cat><meows>1</meows></Cat></item></list>"
+    )
+    assert [type(pet) for pet in result] == [Dog, Cat]
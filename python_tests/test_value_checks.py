@@ -0,0 +1,84 @@
+"""Tests for MinLength, Pattern, Min and Max constraints."""
+
+from decimal import Decimal
+from typing import Annotated, List, Optional
+
+import pytest
+
+from gasp import Deserializable, Max, Min, MinLength, Parser, Pattern
+from gasp.template_helpers import type_to_format_instructions
+
+
+class Flight(Deserializable):
+    airport: Annotated[str, Pattern("^[A-Z]{3}$")]
+    code: Annotated[str, MinLength(3)]
+    seats: Annotated[int, Min(1), Max(850)]
+    fare: Annotated[Optional[float], Min(0)] = None
+
+
+class Schedule(Deserializable):
+    flights: List[Flight]
+
+
+def flight(airport="SFO", code="UA1", seats="100", fare=""):
+    return (
+        f"<Flight><airport>{airport}</airport><code>{code}</code>"
+        f"<seats>{seats}</seats>{fare}</Flight>"
+    )
+
+
+def test_valid_values():
+    result = Parser(Flight).feed(flight(fare="<fare>99.5</fare>"))
+    assert (result.airport, result.code, result.seats, result.fare) == ("SFO", "UA1", 100, 99.5)
+
+
+def test_pattern():
+    with pytest.raises(ValueError, match=r'<airport> "sfo" does not match pattern \^\[A-Z\]\{3\}\$'):
+        Parser(Flight).feed(flight(airport="sfo"))
+
+
+def test_min_length():
+    with pytest.raises(ValueError, match="<code> is 2 characters, under min_length 3"):
+        Parser(Flight).feed(flight(code="U1"))
+
+
+def test_bounds():
+    with pytest.raises(ValueError, match="<seats> is 0, below min 1"):
+        Parser(Flight).feed(flight(seats="0"))
+    with pytest.raises(ValueError, match="<seats> is 900, above max 850"):
+        Parser(Flight).feed(flight(seats="900"))
+
+
+def test_decimal_bounds():
+    with pytest.raises(ValueError, match=r"<fare> is -1\.50, below min 0"):
+        Parser(Flight, decimal_floats=True).feed(flight(fare="<fare>-1.50</fare>"))
+
+
+def test_none_skips_checks():
+    result = Parser(Flight).feed(flight(fare="<fare></fare>"))
+    assert result.fare is None
+
+
+def test_on_error_skip():
+    parser = Parser(Schedule, on_error="skip")
+    result = parser.feed(
+        "<Schedule><flights>"
+        f"<item>{flight(seats='0')}</item><item>{flight()}</item>"
+        "</flights></Schedule>"
+    )
+    assert [f.seats for f in result.flights] == [100]
+
+
+def test_invalid_pattern():
+    class Bad(Deserializable):
+        code: Annotated[str, Pattern("(")]
+
+    with pytest.raises(ValueError, match="Invalid pattern"):
+        Parser(Bad)
+
+
+def test_prompt_describes_checks():
+    instructions = type_to_format_instructions(Flight)
+    assert "(matching ^[A-Z]{3}$)" in instructions
+    assert "(at least 3 characters)" in instructions
+    assert "(>= 1, <= 850)" in instructions
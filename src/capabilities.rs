@@ -0,0 +1,89 @@
+use crate::parser::Limits;
+
+/// What this build supports, so host applications can adapt when builds
+/// differ across environments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    pub version: &'static str,
+    /// Payload formats the parser reads
+    pub formats: Vec<&'static str>,
+    pub features: Features,
+    pub limits: LimitDefaults,
+}
+
+/// The cargo features this build was compiled with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Features {
+    pub ffi: bool,
+    pub intern: bool,
+    pub metrics: bool,
+    pub serde: bool,
+    pub serde_json: bool,
+    pub tracing: bool,
+    pub unsafe_debug: bool,
+}
+
+impl Features {
+    /// Each feature by name, in the order of the fields.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, bool)> {
+        [
+            ("ffi", self.ffi),
+            ("intern", self.intern),
+            ("metrics", self.metrics),
+            ("serde", self.serde),
+            ("serde_json", self.serde_json),
+            ("tracing", self.tracing),
+            ("unsafe_debug", self.unsafe_debug),
+        ]
+        .into_iter()
+    }
+}
+
+/// The limits a Parser starts with. `None` means unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitDefaults {
+    pub max_depth: Option<usize>,
+    pub max_string_length: Option<usize>,
+    pub max_nodes: Option<usize>,
+    pub max_buffer_bytes: Option<usize>,
+}
+
+/// Describe this build: version, payload formats, compiled features and
+/// default limits.
+pub fn capabilities() -> Capabilities {
+    let defaults = Limits::default();
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        formats: vec!["xml"],
+        features: Features {
+            ffi: cfg!(feature = "ffi"),
+            intern: cfg!(feature = "intern"),
+            metrics: cfg!(feature = "metrics"),
+            serde: cfg!(feature = "serde"),
+            serde_json: cfg!(feature = "serde_json"),
+            tracing: cfg!(feature = "tracing"),
+            unsafe_debug: cfg!(feature = "unsafe-debug"),
+        },
+        limits: LimitDefaults {
+            max_depth: defaults.max_depth,
+            max_string_length: defaults.max_string_length,
+            max_nodes: defaults.max_nodes,
+            max_buffer_bytes: defaults.max_buffer_bytes,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_report_this_build() {
+        let caps = capabilities();
+        assert_eq!(caps.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(caps.formats, ["xml"]);
+        assert_eq!(caps.features.ffi, cfg!(feature = "ffi"));
+        assert_eq!(caps.features.iter().count(), 7);
+        assert_eq!(caps.limits.max_depth, None);
+    }
+}
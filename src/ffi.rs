@@ -0,0 +1,203 @@
+//! C ABI for the tag scanner alone, enabled with the `ffi` feature.
+//!
+//! Lets other languages reuse the tolerant tag splitting without the typed
+//! Python parser. The declarations live in `include/gasp_tag_finder.h`.
+
+use std::ffi::{c_char, c_int, c_void, CStr};
+use std::ptr;
+use std::slice;
+
+use crate::tag_finder::{decode_utf8_chunk, TagEvent, TagFinder};
+
+pub const GASP_TAG_OPEN: u32 = 0;
+pub const GASP_TAG_BYTES: u32 = 1;
+pub const GASP_TAG_CLOSE: u32 = 2;
+
+pub const GASP_OK: c_int = 0;
+pub const GASP_ERR_NULL: c_int = -1;
+pub const GASP_ERR_SCAN: c_int = -2;
+
+/// One event, valid only for the duration of the callback. `name` is set for
+/// opens and closes, `text` for bytes; neither is NUL-terminated.
+#[repr(C)]
+pub struct GaspTagEvent {
+    pub kind: u32,
+    pub offset: usize,
+    pub depth: usize,
+    pub name: *const c_char,
+    pub name_len: usize,
+    pub text: *const c_char,
+    pub text_len: usize,
+}
+
+pub type GaspTagEventCallback =
+    Option<extern "C" fn(user_data: *mut c_void, event: *const GaspTagEvent)>;
+
+/// Opaque handle: a tag finder plus any UTF-8 sequence split across chunks.
+pub struct GaspTagFinder {
+    finder: TagFinder,
+    carry: Vec<u8>,
+}
+
+/// Read `len` C strings; null entries are skipped.
+unsafe fn string_list(items: *const *const c_char, len: usize) -> Vec<String> {
+    if items.is_null() {
+        return Vec::new();
+    }
+    slice::from_raw_parts(items, len)
+        .iter()
+        .filter(|item| !item.is_null())
+        .map(|item| CStr::from_ptr(*item).to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Create a tag finder. `wanted` may be empty to report every tag that is
+/// not ignored; entries may be globs such as `tool_*`.
+///
+/// # Safety
+///
+/// `wanted` and `ignored` must each be null or point to that many pointers,
+/// each null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gasp_tag_finder_new(
+    wanted: *const *const c_char,
+    wanted_len: usize,
+    ignored: *const *const c_char,
+    ignored_len: usize,
+) -> *mut GaspTagFinder {
+    let finder = TagFinder::new_with_filter(
+        string_list(wanted, wanted_len),
+        string_list(ignored, ignored_len),
+    );
+    Box::into_raw(Box::new(GaspTagFinder {
+        finder,
+        carry: Vec::new(),
+    }))
+}
+
+/// Feed the next `len` bytes of UTF-8 text, calling `callback` for every
+/// event they complete. Returns `GASP_OK`, or a negative `GASP_ERR_*`.
+///
+/// # Safety
+///
+/// `finder` must come from `gasp_tag_finder_new` and not have been freed,
+/// and `chunk` must point to `len` readable bytes (or be null with `len` 0).
+#[no_mangle]
+pub unsafe extern "C" fn gasp_tag_finder_push(
+    finder: *mut GaspTagFinder,
+    chunk: *const c_char,
+    len: usize,
+    callback: GaspTagEventCallback,
+    user_data: *mut c_void,
+) -> c_int {
+    let (finder, callback) = match (finder.as_mut(), callback) {
+        (Some(finder), Some(callback)) if !chunk.is_null() || len == 0 => (finder, callback),
+        _ => return GASP_ERR_NULL,
+    };
+    let bytes = if len == 0 {
+        &[][..]
+    } else {
+        slice::from_raw_parts(chunk.cast::<u8>(), len)
+    };
+    let text = decode_utf8_chunk(&mut finder.carry, bytes);
+
+    let pushed = finder.finder.push(&text, |offset, event| {
+        let (kind, depth, name, text) = match &event {
+            TagEvent::Open(tag) => (GASP_TAG_OPEN, tag.depth, tag.name.as_str(), ""),
+            TagEvent::Bytes(text) => (GASP_TAG_BYTES, 0, "", text.as_str()),
+            TagEvent::Close(name, depth) => (GASP_TAG_CLOSE, *depth, name.as_str(), ""),
+        };
+        let event = GaspTagEvent {
+            kind,
+            offset,
+            depth,
+            name: if name.is_empty() {
+                ptr::null()
+            } else {
+                name.as_ptr().cast()
+            },
+            name_len: name.len(),
+            text: if text.is_empty() {
+                ptr::null()
+            } else {
+                text.as_ptr().cast()
+            },
+            text_len: text.len(),
+        };
+        callback(user_data, &event);
+        Ok(())
+    });
+    match pushed {
+        Ok(()) => GASP_OK,
+        Err(_) => GASP_ERR_SCAN,
+    }
+}
+
+/// Free a tag finder. Null is ignored.
+///
+/// # Safety
+///
+/// `finder` must be null or come from `gasp_tag_finder_new`, and must not be
+/// used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn gasp_tag_finder_free(finder: *mut GaspTagFinder) {
+    if !finder.is_null() {
+        drop(Box::from_raw(finder));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern "C" fn record(user_data: *mut c_void, event: *const GaspTagEvent) {
+        let events = unsafe { &mut *user_data.cast::<Vec<String>>() };
+        let event = unsafe { &*event };
+        let field = |data: *const c_char, len: usize| {
+            if data.is_null() {
+                String::new()
+            } else {
+                let bytes = unsafe { slice::from_raw_parts(data.cast::<u8>(), len) };
+                String::from_utf8(bytes.to_vec()).unwrap()
+            }
+        };
+        events.push(match event.kind {
+            GASP_TAG_OPEN => format!("{} +{}", event.offset, field(event.name, event.name_len)),
+            GASP_TAG_CLOSE => format!("{} -{}", event.offset, field(event.name, event.name_len)),
+            _ => format!("{} {:?}", event.offset, field(event.text, event.text_len)),
+        });
+    }
+
+    #[test]
+    fn test_push_through_c_abi() {
+        let wanted = [c"Answer".as_ptr()];
+        let ignored = [c"think".as_ptr()];
+        let mut events: Vec<String> = Vec::new();
+        let user_data = (&mut events as *mut Vec<String>).cast::<c_void>();
+        unsafe {
+            let finder = gasp_tag_finder_new(wanted.as_ptr(), 1, ignored.as_ptr(), 1);
+            // "é" split across two pushes
+            let input = "<think>x</think><Answer>caf\u{e9}</Answer>".as_bytes();
+            let split = input.len() - "</Answer>".len() - 1;
+            for part in [&input[..split], &input[split..]] {
+                let status = gasp_tag_finder_push(
+                    finder,
+                    part.as_ptr().cast(),
+                    part.len(),
+                    Some(record),
+                    user_data,
+                );
+                assert_eq!(status, GASP_OK);
+            }
+            assert_eq!(
+                gasp_tag_finder_push(finder, ptr::null(), 4, Some(record), user_data),
+                GASP_ERR_NULL
+            );
+            gasp_tag_finder_free(finder);
+        }
+        assert_eq!(
+            events,
+            vec!["16 +Answer", "24 \"caf\"", "27 \"é\"", "29 -Answer"]
+        );
+    }
+}
@@ -1,16 +1,42 @@
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 
+mod capabilities;
+#[cfg(feature = "ffi")]
+mod ffi;
+#[cfg(feature = "metrics")]
+mod metrics;
 mod parser;
 mod python_types;
+mod redact;
 mod tag_finder;
-mod type_string_parser;
+#[cfg(feature = "serde")]
+mod xml_de;
 mod xml_parser;
 mod xml_types;
 
 use parser::PyParser;
 use xml_parser::StreamParser;
 
+/// The Rust API. The modules themselves are private, so their layout can
+/// change without breaking code that imports from here. Build with
+/// `--no-default-features` to link against libpython from a Rust binary.
+///
+/// The typed parser builds Python objects and needs the GIL, so it is only
+/// reachable through the Python module.
+pub mod prelude {
+    pub use crate::capabilities::{capabilities, Capabilities, Features, LimitDefaults};
+    pub use crate::tag_finder::{
+        format_trace, Events, SkipReason, Tag, TagEvent, TagFinder, TagFinderBuilder, TraceEntry,
+        TraceKind,
+    };
+    #[cfg(feature = "serde")]
+    pub use crate::xml_de::from_value;
+    pub use crate::xml_parser::StreamParser;
+    pub use crate::xml_types::{XmlError, XmlValue, XmlVisitor};
+}
+
 /// A simple StreamParser class for Python
 #[pyclass(name = "StreamParser", unsendable)]
 struct PyStreamParser {
@@ -26,17 +52,15 @@ impl PyStreamParser {
         }
     }
 
-    /// Feed a chunk; returns the parsed value once complete, else `None`.
+    /// Feed a chunk; returns the root element once it closes, else `None`.
     #[pyo3(text_signature = "($self, chunk)")]
     fn parse<'p>(&mut self, py: Python<'p>, chunk: &str) -> PyResult<Option<PyObject>> {
-        // StreamParser is not fully implemented
-        // Just consume the chunk and return None
-        let _ = self
+        let value = self
             .parser
             .step(chunk)
             .map_err(|e| PyValueError::new_err(format!("stream error: {:?}", e)))?;
 
-        Ok(None)
+        Ok(value.map(|value| value.to_object(py)))
     }
 
     /// Check if the parser is done
@@ -46,6 +70,31 @@ impl PyStreamParser {
     }
 }
 
+/// `capabilities::capabilities()` as a dict, so host applications can adapt
+/// when builds differ.
+#[pyfunction]
+#[pyo3(name = "capabilities")]
+fn py_capabilities(py: Python<'_>) -> PyResult<&PyDict> {
+    let report = capabilities::capabilities();
+    let features = PyDict::new(py);
+    for (name, enabled) in report.features.iter() {
+        features.set_item(name, enabled)?;
+    }
+
+    let limits = PyDict::new(py);
+    limits.set_item("max_depth", report.limits.max_depth)?;
+    limits.set_item("max_string_length", report.limits.max_string_length)?;
+    limits.set_item("max_nodes", report.limits.max_nodes)?;
+    limits.set_item("max_buffer_bytes", report.limits.max_buffer_bytes)?;
+
+    let caps = PyDict::new(py);
+    caps.set_item("version", report.version)?;
+    caps.set_item("formats", report.formats)?;
+    caps.set_item("features", features)?;
+    caps.set_item("limits", limits)?;
+    Ok(caps)
+}
+
 /// Python module for parsing structured outputs into typed objects
 #[pymodule]
 fn gasp(py: Python, m: &PyModule) -> PyResult<()> {
@@ -58,5 +107,7 @@ fn gasp(py: Python, m: &PyModule) -> PyResult<()> {
     // Add typed parser
     m.add_class::<PyParser>()?;
 
+    m.add_function(wrap_pyfunction!(py_capabilities, m)?)?;
+
     Ok(())
 }
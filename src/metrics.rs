@@ -0,0 +1,232 @@
+//! Rolling parser metrics, enabled with the `metrics` feature.
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+
+/// Number of recent steps the default recorder computes rates over.
+pub(crate) const DEFAULT_WINDOW: usize = 64;
+
+/// Cost of a single call to `TypedStreamParser::step`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StepSample {
+    pub started: Instant,
+    pub bytes: usize,
+    pub events: usize,
+    pub latency: Duration,
+}
+
+/// Source of the timestamps used for step timings, so time-based behaviour
+/// can be driven by a mock in tests and replays.
+pub(crate) trait Clock: Debug {
+    fn now(&self) -> Instant;
+}
+
+/// The real monotonic clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Receives a sample for every chunk the parser processes.
+pub(crate) trait MetricsRecorder: Debug {
+    fn record(&mut self, sample: StepSample);
+}
+
+/// Rates over the recent window plus lifetime totals.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct MetricsSnapshot {
+    pub chunks_per_sec: f64,
+    pub bytes_per_sec: f64,
+    pub events_per_sec: f64,
+    pub avg_step_latency: Duration,
+    pub total_chunks: u64,
+    pub total_bytes: u64,
+    pub total_events: u64,
+}
+
+/// Default recorder: keeps the last `window` samples.
+#[derive(Debug)]
+pub(crate) struct RollingMetrics {
+    window: usize,
+    samples: VecDeque<StepSample>,
+    total_chunks: u64,
+    total_bytes: u64,
+    total_events: u64,
+}
+
+impl Default for RollingMetrics {
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW)
+    }
+}
+
+impl RollingMetrics {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            samples: VecDeque::new(),
+            total_chunks: 0,
+            total_bytes: 0,
+            total_events: 0,
+        }
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let mut snapshot = MetricsSnapshot {
+            total_chunks: self.total_chunks,
+            total_bytes: self.total_bytes,
+            total_events: self.total_events,
+            ..MetricsSnapshot::default()
+        };
+        let (first, last) = match (self.samples.front(), self.samples.back()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return snapshot,
+        };
+
+        let latency: Duration = self.samples.iter().map(|s| s.latency).sum();
+        snapshot.avg_step_latency = latency / self.samples.len() as u32;
+
+        // Wall time from the start of the oldest step to the end of the newest
+        let span = (last.started + last.latency)
+            .duration_since(first.started)
+            .as_secs_f64();
+        if span > 0.0 {
+            let bytes: usize = self.samples.iter().map(|s| s.bytes).sum();
+            let events: usize = self.samples.iter().map(|s| s.events).sum();
+            snapshot.chunks_per_sec = self.samples.len() as f64 / span;
+            snapshot.bytes_per_sec = bytes as f64 / span;
+            snapshot.events_per_sec = events as f64 / span;
+        }
+        snapshot
+    }
+}
+
+impl MetricsRecorder for RollingMetrics {
+    fn record(&mut self, sample: StepSample) {
+        self.total_chunks += 1;
+        self.total_bytes += sample.bytes as u64;
+        self.total_events += sample.events as u64;
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// A clock that only moves when told to. Clones share the same time, so a
+    /// test can keep one and hand another to the parser.
+    #[derive(Debug, Clone)]
+    struct MockClock {
+        start: Instant,
+        elapsed: Rc<Cell<Duration>>,
+    }
+
+    impl Default for MockClock {
+        fn default() -> Self {
+            Self {
+                start: Instant::now(),
+                elapsed: Rc::new(Cell::new(Duration::ZERO)),
+            }
+        }
+    }
+
+    impl MockClock {
+        fn new() -> Self {
+            Self::default()
+        }
+
+        fn advance(&self, by: Duration) {
+            self.elapsed.set(self.elapsed.get() + by);
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> Instant {
+            self.start + self.elapsed.get()
+        }
+    }
+
+    fn sample(started: Instant, offset_ms: u64, bytes: usize, events: usize) -> StepSample {
+        StepSample {
+            started: started + Duration::from_millis(offset_ms),
+            bytes,
+            events,
+            latency: Duration::from_millis(10),
+        }
+    }
+
+    #[test]
+    fn test_rates_over_window() {
+        let start = Instant::now();
+        let mut metrics = RollingMetrics::new(4);
+        for i in 0..4 {
+            metrics.record(sample(start, i * 250, 100, 5));
+        }
+
+        // 4 steps between t=0 and t=760ms
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.total_chunks, 4);
+        assert_eq!(snapshot.total_bytes, 400);
+        assert_eq!(snapshot.avg_step_latency, Duration::from_millis(10));
+        assert!((snapshot.chunks_per_sec - 4.0 / 0.76).abs() < 1e-9);
+        assert!((snapshot.bytes_per_sec - 400.0 / 0.76).abs() < 1e-9);
+        assert!((snapshot.events_per_sec - 20.0 / 0.76).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_old_samples_leave_window() {
+        let start = Instant::now();
+        let mut metrics = RollingMetrics::new(2);
+        metrics.record(sample(start, 0, 1000, 0));
+        metrics.record(sample(start, 1000, 10, 0));
+        metrics.record(sample(start, 1490, 10, 0));
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.total_chunks, 3);
+        assert_eq!(snapshot.total_bytes, 1020);
+        // Only the last two samples (20 bytes over 500ms) count toward the rate
+        assert!((snapshot.bytes_per_sec - 40.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mock_clock_drives_rates() {
+        let clock = MockClock::new();
+        let parser_clock = clock.clone();
+        let mut metrics = RollingMetrics::new(8);
+        for _ in 0..3 {
+            let started = parser_clock.now();
+            clock.advance(Duration::from_millis(100));
+            metrics.record(StepSample {
+                started,
+                bytes: 50,
+                events: 2,
+                latency: parser_clock.now() - started,
+            });
+        }
+
+        // Exactly 300ms of mock time, independent of how long the test ran
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.avg_step_latency, Duration::from_millis(100));
+        assert!((snapshot.chunks_per_sec - 10.0).abs() < 1e-9);
+        assert!((snapshot.bytes_per_sec - 500.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_empty_snapshot() {
+        assert_eq!(
+            RollingMetrics::default().snapshot(),
+            MetricsSnapshot::default()
+        );
+    }
+}
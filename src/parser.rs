@@ -2,10 +2,388 @@ use log::debug;
 use pyo3::prelude::*;
 use pyo3::types::PyString;
 
-use crate::python_types::PyTypeInfo;
-use crate::tag_finder::{Tag, TagFinder};
+#[cfg(feature = "metrics")]
+use crate::metrics::{
+    Clock, MetricsRecorder, MetricsSnapshot, RollingMetrics, StepSample, SystemClock,
+};
+use crate::python_types::{MaxLength, PyTypeInfo, ValueCheck};
+use crate::redact::Redacted;
+use crate::tag_finder::{decode_utf8_chunk, Tag, TagEvent, TagFinder, TraceEntry};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
+/// Strings at or below this length are interned when the `intern` feature is on.
+#[cfg(feature = "intern")]
+const INTERN_MAX_LEN: usize = 32;
+
+#[cfg(feature = "intern")]
+fn string_to_py(py: Python, value: &str) -> PyObject {
+    if value.len() <= INTERN_MAX_LEN {
+        PyString::intern(py, value).into_py(py)
+    } else {
+        value.into_py(py)
+    }
+}
+
+#[cfg(not(feature = "intern"))]
+fn string_to_py(py: Python, value: &str) -> PyObject {
+    value.into_py(py)
+}
+
+/// Decode XML entities in a single pass, so `&amp;lt;` becomes `&lt;`
+/// rather than `<`. Numeric references (`&#233;`, `&#x1F600;`) are decoded
+/// too, including UTF-16 surrogate pairs written as two references
+/// (`&#xD83D;&#xDE00;`). Unknown entities are kept as written and lone
+/// surrogates become U+FFFD.
+fn decode_entities(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    let mut pending_high: Option<u32> = None; // high surrogate awaiting its pair
+    while let Some(amp) = rest.find('&') {
+        let entity = rest[amp + 1..]
+            .find(';')
+            .map(|semi| &rest[amp + 1..amp + 1 + semi]);
+        let decoded = match entity {
+            Some("amp") => Some('&' as u32),
+            Some("lt") => Some('<' as u32),
+            Some("gt") => Some('>' as u32),
+            Some("quot") => Some('"' as u32),
+            Some("apos") => Some('\'' as u32),
+            Some(e) if e.starts_with("#x") || e.starts_with("#X") => {
+                u32::from_str_radix(&e[2..], 16).ok()
+            }
+            Some(e) if e.starts_with('#') => e[1..].parse::<u32>().ok(),
+            _ => None,
+        };
+
+        let before = &rest[..amp];
+        if pending_high.is_some() && !before.is_empty() {
+            out.push(char::REPLACEMENT_CHARACTER);
+            pending_high = None;
+        }
+        out.push_str(before);
+        let (code, entity) = match (decoded, entity) {
+            (Some(code), Some(entity)) => (code, entity),
+            _ => {
+                if pending_high.take().is_some() {
+                    out.push(char::REPLACEMENT_CHARACTER);
+                }
+                out.push('&');
+                rest = &rest[amp + 1..];
+                continue;
+            }
+        };
+        rest = &rest[amp + entity.len() + 2..];
+
+        match (pending_high.take(), code) {
+            (None, 0xD800..=0xDBFF) => pending_high = Some(code),
+            (Some(high), 0xDC00..=0xDFFF) => {
+                let combined = 0x10000 + ((high - 0xD800) << 10) + (code - 0xDC00);
+                out.push(char::from_u32(combined).unwrap_or(char::REPLACEMENT_CHARACTER));
+            }
+            (Some(_), 0xD800..=0xDBFF) => {
+                out.push(char::REPLACEMENT_CHARACTER);
+                pending_high = Some(code);
+            }
+            (high, code) => {
+                if high.is_some() {
+                    out.push(char::REPLACEMENT_CHARACTER);
+                }
+                out.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+            }
+        }
+    }
+    if pending_high.is_some() {
+        out.push(char::REPLACEMENT_CHARACTER);
+    }
+    out.push_str(rest);
+    out
+}
+
+/// An optionally signed run of ASCII digits.
+fn is_integer_literal(content: &str) -> bool {
+    let digits = content.strip_prefix(['-', '+']).unwrap_or(content);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Parse a Python numeric literal such as `0x1F`, `0o17`, `0b101`,
+/// `1_000_000` or `1_000.5`, as an int or (with `float`) a float.
+fn python_number(py: Python<'_>, content: &str, float: bool) -> PyResult<Option<PyObject>> {
+    let builtins = py.import("builtins")?;
+    let text = content.trim();
+    if let Ok(int) = builtins.getattr("int")?.call1((text, 0)) {
+        return Ok(Some(if float {
+            builtins.getattr("float")?.call1((int,))?.into()
+        } else {
+            int.into()
+        }));
+    }
+    if float {
+        if let Ok(value) = builtins.getattr("float")?.call1((text,)) {
+            return Ok(Some(value.into()));
+        }
+    }
+    Ok(None)
+}
+
+/// True if `text`, the content of a field still being streamed, can no
+/// longer become a value of `kind` however it continues. Surrounding
+/// whitespace is ignored, as it is when the value converts.
+fn cannot_become(kind: &crate::python_types::PyTypeKind, text: &str, python_numbers: bool) -> bool {
+    use crate::python_types::PyTypeKind;
+    let text = text.trim();
+    let unsigned = text.strip_prefix(['+', '-']).unwrap_or(text);
+    let lower = text.to_lowercase();
+    match kind {
+        PyTypeKind::Integer if !python_numbers => !unsigned.bytes().all(|b| b.is_ascii_digit()),
+        PyTypeKind::Float if !python_numbers => {
+            let lower = unsigned.to_lowercase();
+            !(unsigned
+                .bytes()
+                .all(|b| b.is_ascii_digit() || b"._eE+-".contains(&b))
+                || "infinity".starts_with(&lower)
+                || "nan".starts_with(&lower))
+        }
+        PyTypeKind::Boolean => !["true", "false", "yes", "no", "1", "0"]
+            .iter()
+            .any(|word| word.starts_with(&lower)),
+        _ => false,
+    }
+}
+
+fn type_mismatch(tag_name: &str, text: &str, type_info: &PyTypeInfo) -> PyErr {
+    pyo3::exceptions::PyValueError::new_err(format!(
+        "<{}> received {:?} where {} was declared",
+        tag_name, text, type_info.name
+    ))
+}
+
+/// With `strict_types`, raise unless a closed field's text converts to its
+/// declared number or bool type. Empty fields are left to `empty_as`.
+fn check_field_type(frame: &StackFrame, python_numbers: bool) -> PyResult<()> {
+    use crate::python_types::PyTypeKind;
+    let (name, content, type_info) = match frame {
+        StackFrame::Field {
+            name,
+            content,
+            type_info,
+            ..
+        } if !content.trim().is_empty() => (name, content.trim(), type_info),
+        _ => return Ok(()),
+    };
+    let python_number = |float: bool| -> PyResult<bool> {
+        if !python_numbers {
+            return Ok(false);
+        }
+        pyo3::Python::with_gil(|py| Ok(python_number(py, content, float)?.is_some()))
+    };
+    let valid = match type_info.kind {
+        PyTypeKind::Integer => {
+            content.parse::<i64>().is_ok() || is_integer_literal(content) || python_number(false)?
+        }
+        PyTypeKind::Float => content.parse::<f64>().is_ok() || python_number(true)?,
+        PyTypeKind::Boolean => matches!(
+            content.to_lowercase().as_str(),
+            "true" | "false" | "yes" | "no" | "1" | "0"
+        ),
+        _ => true,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(type_mismatch(name, content, type_info))
+    }
+}
+
+/// Raise `ValueError` if a closed field's value fails one of its checks.
+/// String checks are skipped for non-strings and bounds for non-numbers,
+/// such as a `None` from an empty optional field.
+fn check_value(tag_name: &str, object: &PyObject, checks: &[ValueCheck]) -> PyResult<()> {
+    pyo3::Python::with_gil(|py| {
+        let value = object.as_ref(py);
+        let text = value.extract::<&str>().ok();
+        let number = match text {
+            Some(_) => None,
+            None => value.extract::<f64>().ok(),
+        };
+        for check in checks {
+            let failure = match (check, text, number) {
+                (ValueCheck::Pattern(regex), Some(text), _) if !regex.is_match(text) => {
+                    format!("{:?} does not match pattern {}", text, regex.as_str())
+                }
+                (ValueCheck::MinLength(min), Some(text), _) if text.chars().count() < *min => {
+                    format!(
+                        "is {} characters, under min_length {}",
+                        text.chars().count(),
+                        min
+                    )
+                }
+                (ValueCheck::Min(min), _, Some(number)) if number < *min => {
+                    format!("is {}, below min {}", value.str()?, min)
+                }
+                (ValueCheck::Max(max), _, Some(number)) if number > *max => {
+                    format!("is {}, above max {}", value.str()?, max)
+                }
+                _ => continue,
+            };
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "<{}> {}",
+                tag_name, failure
+            )));
+        }
+        Ok(())
+    })
+}
+
+/// What a tag that closes without content becomes, configured per tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EmptyAs {
+    /// `None`
+    Null,
+    /// The empty value of the tag's own type: an instance with no fields
+    /// set, an empty container, or `""` for strings (`None` for numbers)
+    EmptyObject,
+    /// `""`, whatever the declared type
+    EmptyString,
+    /// Raise `ValueError`
+    Error,
+}
+
+impl EmptyAs {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "null" => Some(Self::Null),
+            "empty_object" => Some(Self::EmptyObject),
+            "empty_string" => Some(Self::EmptyString),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::EmptyObject => "empty_object",
+            Self::EmptyString => "empty_string",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// What happens when an object field or dict key appears twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum DuplicateKeys {
+    /// The later value replaces the earlier one
+    #[default]
+    LastWins,
+    /// The later value is dropped
+    FirstWins,
+    /// Raise `ValueError`
+    Error,
+    /// Every value is kept, in order, in a list
+    CollectIntoArray,
+}
+
+impl DuplicateKeys {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "last_wins" => Some(Self::LastWins),
+            "first_wins" => Some(Self::FirstWins),
+            "error" => Some(Self::Error),
+            "collect" => Some(Self::CollectIntoArray),
+            _ => None,
+        }
+    }
+}
+
+/// What happens when building one element of a list, set, tuple or dict
+/// raises, e.g. a field fails validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum OnError {
+    /// Raise the error; the stream is not recoverable
+    #[default]
+    Raise,
+    /// Drop the element and continue after its close tag
+    Skip,
+    /// Put `None` in place of the element and continue after its close tag
+    Null,
+}
+
+impl OnError {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "raise" => Some(Self::Raise),
+            "skip" => Some(Self::Skip),
+            "null" => Some(Self::Null),
+            _ => None,
+        }
+    }
+}
+
+/// An error that `OnError::Skip` or `OnError::Null` recovered from.
 #[derive(Debug, Clone)]
+pub(crate) struct ParseIssue {
+    /// Path of the element that was replaced, e.g. `Batch.items[3]`
+    pub path: String,
+    /// Where the event that raised starts
+    pub pos: SourcePos,
+    pub message: String,
+}
+
+/// `Batch.items[3] at line 1, column 40 (byte 39): <price> ...`
+impl fmt::Display for ParseIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}: {}", self.path, self.pos, self.message)
+    }
+}
+
+/// Lifetime counters for one parser, cheap enough to keep always on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ParserStats {
+    /// Bytes of text passed to `step`
+    pub bytes: u64,
+    /// Tag events handled
+    pub events: u64,
+    /// Partial snapshots built for callers
+    pub snapshots: u64,
+    /// Most frames open at once
+    pub peak_depth: usize,
+}
+
+/// Where in the stream something happened. Line and column are 1-based;
+/// the column counts bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SourcePos {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for SourcePos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {} (byte {})",
+            self.line, self.column, self.offset
+        )
+    }
+}
+
+/// Caps on what one stream may build, so broken or hostile output fails with
+/// an error instead of exhausting memory. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Limits {
+    /// Tags open at once
+    pub max_depth: Option<usize>,
+    /// Bytes of text in a single field
+    pub max_string_length: Option<usize>,
+    /// Tags opened over the whole stream
+    pub max_nodes: Option<usize>,
+    /// Input held but not yet part of a value (see `buffered_bytes`)
+    pub max_buffer_bytes: Option<usize>,
+}
+
+#[derive(Clone)]
 enum StackFrame {
     List {
         tag_name: String,
@@ -19,6 +397,7 @@ enum StackFrame {
         key_type: Option<PyTypeInfo>,
         value_type: Option<PyTypeInfo>,
         current_key: Option<PyObject>,
+        collected: HashSet<usize>, // entries already turned into lists of duplicates
         depth: usize,
     },
     Set {
@@ -38,6 +417,8 @@ enum StackFrame {
         type_info: PyTypeInfo,
         instance: PyObject,
         current_field: Option<String>,
+        seen_fields: HashSet<String>,
+        collected: HashSet<String>, // fields already turned into lists of duplicates
         depth: usize,
     },
     Field {
@@ -48,42 +429,1040 @@ enum StackFrame {
     },
 }
 
+impl StackFrame {
+    fn tag_name_and_depth(&self) -> (&str, usize) {
+        match self {
+            StackFrame::List {
+                tag_name, depth, ..
+            }
+            | StackFrame::Dict {
+                tag_name, depth, ..
+            }
+            | StackFrame::Set {
+                tag_name, depth, ..
+            }
+            | StackFrame::Tuple {
+                tag_name, depth, ..
+            }
+            | StackFrame::Object {
+                tag_name, depth, ..
+            } => (tag_name, *depth),
+            StackFrame::Field { name, depth, .. } => (name, *depth),
+        }
+    }
+
+    /// True when nothing was written inside the tag: only whitespace, or no
+    /// fields or items.
+    fn is_empty(&self) -> bool {
+        match self {
+            StackFrame::List { items, .. }
+            | StackFrame::Set { items, .. }
+            | StackFrame::Tuple { items, .. } => items.is_empty(),
+            StackFrame::Dict { entries, .. } => entries.is_empty(),
+            StackFrame::Object { seen_fields, .. } => seen_fields.is_empty(),
+            StackFrame::Field { content, .. } => content.trim().is_empty(),
+        }
+    }
+
+    /// Store a completed child value in this frame. A field or key seen
+    /// before is resolved by `duplicates`.
+    fn attach_child(&mut self, child_object: PyObject, duplicates: DuplicateKeys) -> PyResult<()> {
+        match self {
+            StackFrame::List { items, .. } => items.push(child_object),
+            StackFrame::Set { items, .. } => items.push(child_object),
+            StackFrame::Tuple { items, .. } => items.push(child_object),
+            StackFrame::Dict {
+                tag_name,
+                entries,
+                current_key,
+                collected,
+                ..
+            } => {
+                if let Some(key) = current_key.take() {
+                    let existing = pyo3::Python::with_gil(|py| -> PyResult<Option<usize>> {
+                        for (idx, (existing, _)) in entries.iter().enumerate() {
+                            if existing.as_ref(py).eq(key.as_ref(py))? {
+                                return Ok(Some(idx));
+                            }
+                        }
+                        Ok(None)
+                    })?;
+                    match (existing, duplicates) {
+                        (None, _) | (Some(_), DuplicateKeys::LastWins) => {
+                            entries.push((key, child_object))
+                        }
+                        (Some(_), DuplicateKeys::FirstWins) => {}
+                        (Some(_), DuplicateKeys::Error) => {
+                            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                                "Duplicate key {} in <{}>",
+                                pyo3::Python::with_gil(|py| key.as_ref(py).to_string()),
+                                tag_name
+                            )));
+                        }
+                        (Some(idx), DuplicateKeys::CollectIntoArray) => {
+                            pyo3::Python::with_gil(|py| -> PyResult<()> {
+                                let value = &mut entries[idx].1;
+                                if collected.insert(idx) {
+                                    let list = pyo3::types::PyList::new(py, [value.clone_ref(py)]);
+                                    *value = list.into();
+                                }
+                                value
+                                    .as_ref(py)
+                                    .downcast::<pyo3::types::PyList>()?
+                                    .append(child_object)
+                            })?;
+                        }
+                    }
+                }
+            }
+            StackFrame::Object {
+                tag_name,
+                instance,
+                current_field,
+                seen_fields,
+                collected,
+                ..
+            } => {
+                if let Some(field_name) = current_field.take() {
+                    let value = if !seen_fields.contains(&field_name) {
+                        child_object
+                    } else {
+                        match duplicates {
+                            DuplicateKeys::LastWins => child_object,
+                            DuplicateKeys::FirstWins => return Ok(()),
+                            DuplicateKeys::Error => {
+                                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                                    "Duplicate field '{}' in <{}>",
+                                    field_name, tag_name
+                                )));
+                            }
+                            DuplicateKeys::CollectIntoArray => {
+                                pyo3::Python::with_gil(|py| -> PyResult<PyObject> {
+                                    let current =
+                                        instance.as_ref(py).getattr(field_name.as_str())?;
+                                    if collected.insert(field_name.clone()) {
+                                        Ok(pyo3::types::PyList::new(
+                                            py,
+                                            [current, child_object.as_ref(py)],
+                                        )
+                                        .into())
+                                    } else {
+                                        current
+                                            .downcast::<pyo3::types::PyList>()?
+                                            .append(child_object)?;
+                                        Ok(current.into())
+                                    }
+                                })?
+                            }
+                        }
+                    };
+                    pyo3::Python::with_gil(|py| {
+                        let _ = instance.as_ref(py).setattr(field_name.as_str(), value);
+                    });
+                    seen_fields.insert(field_name);
+                }
+            }
+            StackFrame::Field { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for StackFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            StackFrame::List { .. } => "List",
+            StackFrame::Dict { .. } => "Dict",
+            StackFrame::Set { .. } => "Set",
+            StackFrame::Tuple { .. } => "Tuple",
+            StackFrame::Object { .. } => "Object",
+            StackFrame::Field { .. } => "Field",
+        };
+        let (tag_name, depth) = self.tag_name_and_depth();
+        let mut frame = f.debug_struct(kind);
+        frame.field("tag_name", &tag_name).field("depth", &depth);
+        match self {
+            StackFrame::Object {
+                type_info,
+                current_field,
+                ..
+            } => frame
+                .field("type", &type_info.name)
+                .field("current_field", current_field),
+            StackFrame::Field {
+                content, type_info, ..
+            } => frame
+                .field("type", &type_info.name)
+                .field("content", &Redacted(content)),
+            StackFrame::List { items, .. }
+            | StackFrame::Set { items, .. }
+            | StackFrame::Tuple { items, .. } => frame.field("items", &items.len()),
+            StackFrame::Dict { entries, .. } => frame.field("entries", &entries.len()),
+        };
+        frame.finish()
+    }
+}
+
+/// A parsed value together with the root tag that produced it.
+#[derive(Debug, Clone)]
+pub(crate) struct TaggedValue {
+    pub tag: String,
+    pub value: PyObject,
+    pub complete: bool,
+}
+
 /// Wrapper for the StreamParser that handles typed conversions
-#[derive(Debug)]
-pub struct TypedStreamParser {
+pub(crate) struct TypedStreamParser {
     tag_finder: TagFinder,
     type_info: Option<PyTypeInfo>,
     is_done: bool,
     stack: Vec<StackFrame>,
     stack_based_result: Option<PyObject>,
     depth: usize,
+    unclosed_tags: Vec<String>,       // tags force-closed by finish()
+    unclosed_paths: Vec<String>,      // paths of the frames force-closed by finish()
+    eager_complete: bool,             // complete once every root field has closed
+    multiple: bool,                   // keep parsing after each root value completes
+    results: Vec<(String, PyObject)>, // completed root values with their tag names
+    last_root_tag: Option<String>,    // tag of the most recently completed root
+    metadata_tags: HashMap<String, String>, // lowercase name -> name as configured
+    metadata: HashMap<String, String>, // completed metadata text by configured name
+    current_metadata: Option<(String, usize, String)>, // (name, depth, text) being captured
+    string_deltas: bool,              // record text appended to string fields
+    deltas: Vec<(String, String)>,    // (path, appended text) since last take
+    subscriptions: HashMap<String, Vec<PyObject>>, // path -> callbacks for completed values
+    decimal_floats: bool,             // parse floats as decimal.Decimal
+    python_numbers: bool,             // accept 0x1F, 1_000 and friends in number fields
+    python_literals: bool,            // accept None and (1, 2) style container text
+    strict_types: bool,               // raise when a field's text can't be its declared type
+    literal_text: Option<(usize, usize, String)>, // (frame index, depth, text) inside a container
+    utf8_carry: Vec<u8>,              // incomplete UTF-8 sequence from the last byte chunk
+    coalesce_bytes: usize,            // hold chunks until this many bytes are pending; 0 is off
+    pending: String,                  // small chunks held back by coalescing
+    last_step: Option<PyObject>,      // value returned for a chunk that was held back
+    empty_as: HashMap<String, EmptyAs>, // lowercase tag name (or "*") -> policy for empty tags
+    empty_paths: Vec<(String, EmptyAs)>, // where an empty_as policy was applied
+    truncated_paths: Vec<(String, usize)>, // (path, original chars) cut to a MaxLength
+    duplicate_keys: DuplicateKeys,    // repeated object fields and dict keys
+    on_error: OnError,                // failures inside container elements
+    issues: Vec<ParseIssue>,          // errors recovered from under on_error
+    skip_depth: Option<usize>,        // depth of a failed element whose events are dropped
+    pending_union: Option<(Tag, PyTypeInfo, Option<String>)>, // union tag waiting for a child to pick a member, and any _type text
+    limits: Limits,
+    nodes: usize,                // tags opened so far, for limits.max_nodes
+    limit_error: Option<String>, // set once a limit is exceeded; the stream is dead
+    stats: ParserStats,
+    line_starts: Vec<usize>, // stream offset of the start of every line after the first
+    #[cfg(feature = "metrics")]
+    metrics: RollingMetrics,
+    #[cfg(feature = "metrics")]
+    recorder: Option<Box<dyn MetricsRecorder>>, // extra recorder fed the same samples
+    #[cfg(feature = "metrics")]
+    clock: Box<dyn Clock>, // timestamps for step samples
+}
+
+impl fmt::Debug for TypedStreamParser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parser = f.debug_struct("TypedStreamParser");
+        parser
+            .field("tag_finder", &self.tag_finder)
+            .field("type", &self.type_info.as_ref().map(|t| &t.name))
+            .field("is_done", &self.is_done)
+            .field("stack", &self.stack)
+            .field("stack_based_result", &self.stack_based_result)
+            .field("depth", &self.depth)
+            .field("unclosed_tags", &self.unclosed_tags)
+            .field("unclosed_paths", &self.unclosed_paths)
+            .field("eager_complete", &self.eager_complete)
+            .field("multiple", &self.multiple)
+            .field("results", &self.results.len())
+            .field("last_root_tag", &self.last_root_tag)
+            .field("metadata_tags", &self.metadata_tags)
+            .field(
+                "metadata",
+                &self
+                    .metadata
+                    .iter()
+                    .map(|(name, text)| (name, Redacted(text)))
+                    .collect::<HashMap<_, _>>(),
+            )
+            .field(
+                "current_metadata",
+                &self
+                    .current_metadata
+                    .as_ref()
+                    .map(|(name, depth, text)| (name, depth, Redacted(text))),
+            )
+            .field("string_deltas", &self.string_deltas)
+            .field(
+                "deltas",
+                &self
+                    .deltas
+                    .iter()
+                    .map(|(path, text)| (path, Redacted(text)))
+                    .collect::<Vec<_>>(),
+            )
+            .field("subscriptions", &self.subscriptions.keys())
+            .field("decimal_floats", &self.decimal_floats)
+            .field("python_numbers", &self.python_numbers)
+            .field("python_literals", &self.python_literals)
+            .field("strict_types", &self.strict_types)
+            .field(
+                "literal_text",
+                &self
+                    .literal_text
+                    .as_ref()
+                    .map(|(idx, depth, text)| (idx, depth, Redacted(text))),
+            )
+            .field("utf8_carry", &Redacted(&self.utf8_carry))
+            .field("coalesce_bytes", &self.coalesce_bytes)
+            .field("pending", &Redacted(&self.pending))
+            .field("last_step", &self.last_step)
+            .field("empty_as", &self.empty_as)
+            .field("empty_paths", &self.empty_paths)
+            .field("truncated_paths", &self.truncated_paths)
+            .field("duplicate_keys", &self.duplicate_keys)
+            .field("on_error", &self.on_error)
+            .field("issues", &self.issues.len())
+            .field("skip_depth", &self.skip_depth)
+            .field(
+                "pending_union",
+                &self
+                    .pending_union
+                    .as_ref()
+                    .map(|(tag, union, key)| (&tag.name, &union.name, key)),
+            )
+            .field("limits", &self.limits)
+            .field("nodes", &self.nodes)
+            .field("limit_error", &self.limit_error)
+            .field("stats", &self.stats)
+            .field("lines", &(self.line_starts.len() + 1));
+        #[cfg(feature = "metrics")]
+        parser
+            .field("metrics", &self.metrics)
+            .field("recorder", &self.recorder)
+            .field("clock", &self.clock);
+        parser.finish()
+    }
 }
 
 impl TypedStreamParser {
     pub fn new(wanted_tags: Vec<String>, ignored_tags: Vec<String>) -> Self {
-        Self {
-            tag_finder: TagFinder::new_with_filter(wanted_tags, ignored_tags),
-            type_info: None,
-            is_done: false,
-            stack: Vec::new(),
-            stack_based_result: None,
-            depth: 0,
-        }
+        Self::build(None, wanted_tags, ignored_tags)
     }
 
     pub fn with_type(
         type_info: PyTypeInfo,
         wanted_tags: Vec<String>,
         ignored_tags: Vec<String>,
+    ) -> Self {
+        Self::build(Some(type_info), wanted_tags, ignored_tags)
+    }
+
+    fn build(
+        type_info: Option<PyTypeInfo>,
+        wanted_tags: Vec<String>,
+        ignored_tags: Vec<String>,
     ) -> Self {
         Self {
             tag_finder: TagFinder::new_with_filter(wanted_tags, ignored_tags),
-            type_info: Some(type_info),
+            type_info,
             is_done: false,
             stack: Vec::new(),
             stack_based_result: None,
             depth: 0,
+            unclosed_tags: Vec::new(),
+            unclosed_paths: Vec::new(),
+            eager_complete: false,
+            multiple: false,
+            results: Vec::new(),
+            last_root_tag: None,
+            metadata_tags: HashMap::new(),
+            metadata: HashMap::new(),
+            current_metadata: None,
+            string_deltas: false,
+            deltas: Vec::new(),
+            subscriptions: HashMap::new(),
+            decimal_floats: false,
+            python_numbers: false,
+            python_literals: false,
+            strict_types: false,
+            literal_text: None,
+            utf8_carry: Vec::new(),
+            coalesce_bytes: 0,
+            pending: String::new(),
+            last_step: None,
+            empty_as: HashMap::new(),
+            empty_paths: Vec::new(),
+            truncated_paths: Vec::new(),
+            duplicate_keys: DuplicateKeys::default(),
+            on_error: OnError::default(),
+            issues: Vec::new(),
+            skip_depth: None,
+            pending_union: None,
+            limits: Limits::default(),
+            nodes: 0,
+            limit_error: None,
+            stats: ParserStats::default(),
+            line_starts: Vec::new(),
+            #[cfg(feature = "metrics")]
+            metrics: RollingMetrics::default(),
+            #[cfg(feature = "metrics")]
+            recorder: None,
+            #[cfg(feature = "metrics")]
+            clock: Box::new(SystemClock),
+        }
+    }
+
+    /// Capture the text of these tags separately from the main payload, e.g.
+    /// `<Meta>{"model":"x"}</Meta>` sent ahead of the answer. Metadata is
+    /// only recognised outside the main payload.
+    pub fn with_metadata_tags(mut self, tags: Vec<String>) -> Self {
+        for tag in tags {
+            self.tag_finder.add_wanted(&tag);
+            self.metadata_tags.insert(tag.to_lowercase(), tag);
+        }
+        self
+    }
+
+    /// Report a class root as complete as soon as each of its fields has
+    /// closed, without waiting for the root's own closing tag.
+    pub fn with_eager_complete(mut self, eager_complete: bool) -> Self {
+        self.eager_complete = eager_complete;
+        self
+    }
+
+    /// Keep parsing after a root value completes, collecting every value in
+    /// the stream (e.g. several `<Action>` tags) instead of only the first.
+    pub fn with_multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
+
+    /// Record the text appended to each field as it streams, so callers can
+    /// update a UI incrementally instead of diffing whole snapshots.
+    pub fn with_string_deltas(mut self, string_deltas: bool) -> Self {
+        self.string_deltas = string_deltas;
+        self
+    }
+
+    /// Parse float fields as `decimal.Decimal` so their digits are kept
+    /// exactly instead of being rounded to the nearest `f64`.
+    pub fn with_decimal_floats(mut self, decimal_floats: bool) -> Self {
+        self.decimal_floats = decimal_floats;
+        self
+    }
+
+    /// Also accept Python numeric literals in number fields: hex, octal and
+    /// binary integers and `_` digit separators. `NaN` and `Infinity` are
+    /// accepted in float fields either way.
+    pub fn with_python_numbers(mut self, python_numbers: bool) -> Self {
+        self.python_numbers = python_numbers;
+        self
+    }
+
+    /// Accept Python literals written by models that have seen Python code:
+    /// a field whose text is `None` becomes `None`, and a list, set or tuple
+    /// written as `[1, 2]`, `(1, 2)` or `{1, 2}` instead of `<item>` tags is
+    /// read with `ast.literal_eval`. `True`/`False` are booleans either way.
+    pub fn with_python_literals(mut self, python_literals: bool) -> Self {
+        self.python_literals = python_literals;
+        self
+    }
+
+    /// Hold chunks shorter than `bytes` and parse them together once that
+    /// many bytes are pending or a chunk ends a tag (contains `>`), so a
+    /// stream of 2-5 byte tokens doesn't pay the per-step cost for each
+    /// one. A held chunk returns the previous value. 0 (the default) parses
+    /// every chunk as it arrives.
+    pub fn with_coalesce_bytes(mut self, bytes: usize) -> Self {
+        self.coalesce_bytes = bytes;
+        self
+    }
+
+    /// Raise `ValueError` as soon as a number or bool field's text can no
+    /// longer be its declared type (e.g. `<age>ab`), instead of reading the
+    /// field as `None` or `False` once it closes, so a bad generation can
+    /// be stopped early.
+    pub fn with_strict_types(mut self, strict_types: bool) -> Self {
+        self.strict_types = strict_types;
+        self
+    }
+
+    /// Keep the prose outside the payload tags, such as reasoning written
+    /// before the answer, instead of discarding it.
+    pub fn with_passthrough_text(mut self, passthrough_text: bool) -> Self {
+        self.tag_finder.set_passthrough(passthrough_text);
+        self
+    }
+
+    /// Record every tag event, and every tag the finder skipped, with its
+    /// byte offset in the stream. See `tag_trace`.
+    pub fn with_tag_trace(mut self, trace: bool) -> Self {
+        self.tag_finder.set_trace(trace);
+        self
+    }
+
+    /// Decide what tags that close without content become, keyed by tag
+    /// name with `"*"` as the fallback. Tags without a policy are converted
+    /// as usual.
+    pub fn with_empty_as(mut self, policies: HashMap<String, EmptyAs>) -> Self {
+        self.empty_as = policies
+            .into_iter()
+            .map(|(tag, policy)| (tag.to_lowercase(), policy))
+            .collect();
+        self
+    }
+
+    /// Choose how a repeated object field or dict key is resolved. The default
+    /// keeps the last value; stricter consumers can reject or collect them.
+    pub fn with_duplicate_keys(mut self, duplicate_keys: DuplicateKeys) -> Self {
+        self.duplicate_keys = duplicate_keys;
+        self
+    }
+
+    /// Choose whether an error while building a container element ends the
+    /// stream or only costs that element. See `issues`.
+    pub fn with_on_error(mut self, on_error: OnError) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    /// Fail with `ValueError` once the stream goes past any of `limits`.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    fn limit_violation(&self) -> Option<String> {
+        if let Some(max) = self.limits.max_depth {
+            let depth = self.stack.len().max(self.depth);
+            if depth > max {
+                return Some(format!("nesting depth {} > max_depth {}", depth, max));
+            }
+        }
+        if let Some(max) = self.limits.max_string_length {
+            if let Some(StackFrame::Field { name, content, .. }) = self.stack.last() {
+                if content.len() > max {
+                    return Some(format!(
+                        "<{}> text of {} bytes > max_string_length {}",
+                        name,
+                        content.len(),
+                        max
+                    ));
+                }
+            }
+        }
+        if let Some(max) = self.limits.max_nodes {
+            if self.nodes > max {
+                return Some(format!("{} tags > max_nodes {}", self.nodes, max));
+            }
+        }
+        if let Some(max) = self.limits.max_buffer_bytes {
+            let buffered = self.buffered_bytes();
+            if buffered > max {
+                return Some(format!(
+                    "{} buffered bytes > max_buffer_bytes {}",
+                    buffered, max
+                ));
+            }
+        }
+        None
+    }
+
+    /// Error out, and drop everything built so far, if a limit is exceeded.
+    /// Every later step fails with the same error.
+    fn enforce_limits(&mut self) -> PyResult<()> {
+        if self.limit_error.is_none() {
+            self.limit_error = self
+                .limit_violation()
+                .map(|violation| format!("Limit exceeded: {}", violation));
+        }
+        match &self.limit_error {
+            Some(message) => {
+                self.stack.clear();
+                Err(pyo3::exceptions::PyValueError::new_err(message.clone()))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// The tag events recorded since the parser was created, when built
+    /// `with_tag_trace`.
+    pub fn tag_trace(&self) -> &[TraceEntry] {
+        self.tag_finder.trace()
+    }
+
+    /// Take the text outside payload tags seen since the last call.
+    pub fn take_text(&mut self) -> String {
+        self.tag_finder.take_passthrough()
+    }
+
+    /// Take the `(path, appended)` pairs recorded since the last call.
+    pub fn take_deltas(&mut self) -> Vec<(String, String)> {
+        std::mem::take(&mut self.deltas)
+    }
+
+    /// Path of the value currently being built, e.g. `Person.hobbies[1]`.
+    fn current_path(&self) -> String {
+        self.frame_paths().pop().unwrap_or_default()
+    }
+
+    /// Line and column of the stream byte at `offset`.
+    pub fn position(&self, offset: usize) -> SourcePos {
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let line_start = line.checked_sub(1).map_or(0, |idx| self.line_starts[idx]);
+        SourcePos {
+            offset,
+            line: line + 1,
+            column: offset - line_start + 1,
+        }
+    }
+
+    /// Add where it happened to a `ValueError` raised while handling the
+    /// event at `offset`: the message gains the position and the path of the
+    /// innermost open value, and the exception gets `offset`, `line`,
+    /// `column` and `path` attributes. Other exceptions, including subclasses
+    /// such as pydantic's `ValidationError`, pass through unchanged.
+    fn locate_error(&self, err: PyErr, offset: usize) -> PyErr {
+        pyo3::Python::with_gil(|py| {
+            let value_error = py.get_type::<pyo3::exceptions::PyValueError>();
+            if !err.get_type(py).is(value_error) {
+                return err;
+            }
+            let pos = self.position(offset);
+            let path = self.current_path();
+            let message = if path.is_empty() {
+                format!("{} at {}", err.value(py), pos)
+            } else {
+                format!("{} at {}, in {}", err.value(py), pos, path)
+            };
+            let located = pyo3::exceptions::PyValueError::new_err(message);
+            let value = located.value(py);
+            let attrs = [
+                ("offset", pos.offset.into_py(py)),
+                ("line", pos.line.into_py(py)),
+                ("column", pos.column.into_py(py)),
+                ("path", path.into_py(py)),
+            ];
+            for (name, attr) in attrs {
+                if let Err(err) = value.setattr(name, attr) {
+                    return err;
+                }
+            }
+            located
+        })
+    }
+
+    /// Under `OnError::Skip`/`Null`, abandon the container element that was
+    /// being built when `err` was raised and drop the rest of its events.
+    /// `open_frames` is the stack length before the failing `event`. Hands
+    /// `err` back when there is no enclosing element to give up on.
+    fn recover(
+        &mut self,
+        err: PyErr,
+        offset: usize,
+        open_frames: usize,
+        event: &TagEvent,
+    ) -> PyResult<()> {
+        if self.on_error == OnError::Raise {
+            return Err(err);
+        }
+        let is_exception =
+            pyo3::Python::with_gil(|py| err.is_instance_of::<pyo3::exceptions::PyException>(py));
+        // The innermost container that was open before the event
+        let container = self.stack[..open_frames.min(self.stack.len())]
+            .iter()
+            .rposition(|frame| {
+                matches!(
+                    frame,
+                    StackFrame::List { .. }
+                        | StackFrame::Set { .. }
+                        | StackFrame::Tuple { .. }
+                        | StackFrame::Dict { .. }
+                )
+            });
+        let container = match container {
+            Some(idx) if is_exception => idx,
+            _ => return Err(err),
+        };
+
+        let mut path = self.frame_paths();
+        let path = if path.len() > container + 1 {
+            path.swap_remove(container + 1)
+        } else {
+            self.current_path()
+        };
+        let message = pyo3::Python::with_gil(|py| err.value(py).to_string());
+        debug!("Recovering from error in {}: {}", path, message);
+        self.issues.push(ParseIssue {
+            path,
+            pos: self.position(offset),
+            message,
+        });
+
+        // Events up to the element's close tag belong to the dropped element
+        self.skip_depth = match (self.stack.get(container + 1), event) {
+            (Some(element), TagEvent::Close(_, depth))
+                if *depth <= element.tag_name_and_depth().1 =>
+            {
+                None
+            }
+            (Some(element), _) => Some(element.tag_name_and_depth().1),
+            // The element's own open tag failed
+            (None, TagEvent::Open(tag)) => Some(tag.depth),
+            (None, _) => None,
+        };
+        self.stack.truncate(container + 1);
+        if self.on_error == OnError::Null {
+            let none = pyo3::Python::with_gil(|py| py.None());
+            // A keyed slot that cannot take the None is simply left out
+            let _ = self.stack[container].attach_child(none, self.duplicate_keys);
+        }
+        Ok(())
+    }
+
+    /// Path of every open frame, outermost first.
+    fn frame_paths(&self) -> Vec<String> {
+        let mut paths = Vec::with_capacity(self.stack.len());
+        let mut path = String::new();
+        for (idx, frame) in self.stack.iter().enumerate() {
+            let name = frame.tag_name_and_depth().0;
+            match idx.checked_sub(1).map(|parent| &self.stack[parent]) {
+                None => path.push_str(name),
+                Some(StackFrame::List { items, .. })
+                | Some(StackFrame::Set { items, .. })
+                | Some(StackFrame::Tuple { items, .. }) => {
+                    path.push_str(&format!("[{}]", items.len()))
+                }
+                Some(StackFrame::Dict {
+                    current_key: Some(key),
+                    ..
+                }) => pyo3::Python::with_gil(|py| path.push_str(&format!("[{}]", key.as_ref(py)))),
+                Some(_) => {
+                    path.push('.');
+                    path.push_str(name);
+                }
+            }
+            paths.push(path.clone());
+        }
+        paths
+    }
+
+    /// Call `callback(path, value)` each time a value at `path` (same format
+    /// as the delta paths) is completed, instead of walking every snapshot.
+    pub fn subscribe(&mut self, path: String, callback: PyObject) {
+        self.subscriptions.entry(path).or_default().push(callback);
+    }
+
+    fn notify_subscribers(&self, path: &str, value: &PyObject) -> PyResult<()> {
+        if let Some(callbacks) = self.subscriptions.get(path) {
+            pyo3::Python::with_gil(|py| {
+                for callback in callbacks {
+                    callback.call1(py, (path, value.clone_ref(py)))?;
+                }
+                Ok(())
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Pop the top frame and convert it to a Python object, notifying any
+    /// subscribers to its path.
+    fn pop_frame_object(&mut self) -> PyResult<PyObject> {
+        let path = (!self.subscriptions.is_empty()).then(|| self.current_path());
+        let frame = self.stack.pop().unwrap();
+        let object = self.frame_to_pyobject(frame)?;
+        if let Some(path) = path {
+            self.notify_subscribers(&path, &object)?;
+        }
+        Ok(object)
+    }
+
+    /// The replacement for `frame` if it is empty and its tag has an
+    /// `empty_as` policy.
+    fn empty_value(&self, frame: &StackFrame) -> PyResult<Option<(EmptyAs, PyObject)>> {
+        if self.empty_as.is_empty() || !frame.is_empty() {
+            return Ok(None);
+        }
+        let tag_name = frame.tag_name_and_depth().0;
+        let policy = match self
+            .empty_as
+            .get(&tag_name.to_lowercase())
+            .or_else(|| self.empty_as.get("*"))
+        {
+            Some(policy) => *policy,
+            None => return Ok(None),
+        };
+        let value = pyo3::Python::with_gil(|py| match policy {
+            EmptyAs::Null => Ok(py.None()),
+            EmptyAs::EmptyString => Ok(string_to_py(py, "")),
+            EmptyAs::EmptyObject => match frame {
+                StackFrame::Field { type_info, .. }
+                    if type_info.kind == crate::python_types::PyTypeKind::String =>
+                {
+                    Ok(string_to_py(py, ""))
+                }
+                StackFrame::Field { .. } => Ok(py.None()),
+                _ => self.frame_to_pyobject(frame.clone()),
+            },
+            EmptyAs::Error => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "<{}> is empty",
+                tag_name
+            ))),
+        })?;
+        Ok(Some((policy, value)))
+    }
+
+    /// Like `pop_frame_object`, for a frame closed by its own end tag: if it
+    /// is empty, its `empty_as` policy decides the value.
+    fn pop_closed_frame_object(&mut self) -> PyResult<PyObject> {
+        if self.python_literals {
+            if let Some(object) = self.pop_python_literal()? {
+                return Ok(object);
+            }
+        }
+        let empty = match self.stack.last() {
+            Some(frame) => self.empty_value(frame)?,
+            None => None,
+        };
+        match empty {
+            Some((policy, object)) => {
+                let path = self.current_path();
+                self.stack.pop();
+                self.notify_subscribers(&path, &object)?;
+                self.empty_paths.push((path, policy));
+                Ok(object)
+            }
+            None => match self.stack.last() {
+                Some(StackFrame::Field {
+                    name, type_info, ..
+                }) if self.strict_types
+                    || type_info.max_length.is_some()
+                    || !type_info.checks.is_empty() =>
+                {
+                    let path = self.current_path();
+                    let frame = self.stack.pop().unwrap();
+                    let object = self.checked_field_object(frame, &path)?;
+                    self.notify_subscribers(&path, &object)?;
+                    Ok(object)
+                }
+                _ => self.pop_frame_object(),
+            },
+        }
+    }
+
+    /// Convert a closed field with its `strict_types`, `max_length` and
+    /// value checks applied.
+    fn checked_field_object(&mut self, frame: StackFrame, path: &str) -> PyResult<PyObject> {
+        let (tag_name, max_length, checks) = match &frame {
+            StackFrame::Field {
+                name, type_info, ..
+            } => (name.clone(), type_info.max_length, type_info.checks.clone()),
+            _ => return self.frame_to_pyobject(frame),
+        };
+        if self.strict_types {
+            check_field_type(&frame, self.python_numbers)?;
+        }
+        let mut object = self.frame_to_pyobject(frame)?;
+        if let Some(max_length) = max_length {
+            object = self.apply_max_length(&tag_name, path, object, max_length)?;
+        }
+        check_value(&tag_name, &object, &checks)?;
+        Ok(object)
+    }
+
+    /// With `python_literals`, the value of the closing frame when it was
+    /// written as a Python literal: `None` for a field, or container items
+    /// given as `(1, 2)`-style text, which are added to the frame.
+    fn pop_python_literal(&mut self) -> PyResult<Option<PyObject>> {
+        let idx = match self.stack.len().checked_sub(1) {
+            Some(idx) => idx,
+            None => return Ok(None),
+        };
+        let (tag_name, depth) = {
+            let (name, depth) = self.stack[idx].tag_name_and_depth();
+            (name.to_string(), depth)
+        };
+        let text = match self.literal_text.take() {
+            Some((text_idx, text_depth, text)) if text_idx == idx && text_depth == depth => text,
+            other => {
+                self.literal_text = other;
+                String::new()
+            }
+        };
+        match &self.stack[idx] {
+            StackFrame::Field { content, .. } if content.trim() == "None" => {
+                let path = self.current_path();
+                self.stack.pop();
+                let none = pyo3::Python::with_gil(|py| py.None());
+                self.notify_subscribers(&path, &none)?;
+                return Ok(Some(none));
+            }
+            StackFrame::List { items, .. }
+            | StackFrame::Set { items, .. }
+            | StackFrame::Tuple { items, .. }
+                if items.is_empty() && !text.trim().is_empty() => {}
+            _ => return Ok(None),
+        }
+        let literal = pyo3::Python::with_gil(|py| -> PyResult<Vec<(PyObject, String)>> {
+            let literal_eval = py.import("ast")?.getattr("literal_eval")?;
+            let value = match literal_eval.call1((text.trim(),)) {
+                Ok(value) => value,
+                Err(_) => {
+                    debug!("<{}> text is not a Python literal", tag_name);
+                    return Ok(Vec::new());
+                }
+            };
+            let is_sequence = value.is_instance_of::<pyo3::types::PyList>()
+                || value.is_instance_of::<pyo3::types::PyTuple>()
+                || value.is_instance_of::<pyo3::types::PySet>();
+            if !is_sequence {
+                return Ok(Vec::new());
+            }
+            value
+                .iter()?
+                .map(|item| {
+                    let item = item?;
+                    let text = match item.extract::<String>() {
+                        Ok(text) => text,
+                        Err(_) => item.str()?.to_string(),
+                    };
+                    Ok((item.into(), text))
+                })
+                .collect()
+        })?;
+        // Items are converted and checked as if each had been an <item> tag
+        let path = self.current_path();
+        let mut converted = Vec::with_capacity(literal.len());
+        for (index, (item, text)) in literal.into_iter().enumerate() {
+            let item_type = match &self.stack[idx] {
+                StackFrame::List { item_type, .. } | StackFrame::Set { item_type, .. } => {
+                    Some(item_type)
+                }
+                StackFrame::Tuple { types, .. } => types.get(index),
+                _ => None,
+            };
+            converted.push(match item_type {
+                Some(item_type) if item_type.is_primitive() => {
+                    let frame = StackFrame::Field {
+                        name: "item".to_string(),
+                        content: text,
+                        type_info: item_type.clone(),
+                        depth: depth + 1,
+                    };
+                    self.checked_field_object(frame, &format!("{}[{}]", path, index))?
+                }
+                _ => item,
+            });
         }
+        if let StackFrame::List { items, .. }
+        | StackFrame::Set { items, .. }
+        | StackFrame::Tuple { items, .. } = &mut self.stack[idx]
+        {
+            items.extend(converted);
+        }
+        Ok(None)
+    }
+
+    /// Raise, or truncate and record, a string over its `MaxLength`.
+    fn apply_max_length(
+        &mut self,
+        tag_name: &str,
+        path: &str,
+        object: PyObject,
+        max_length: MaxLength,
+    ) -> PyResult<PyObject> {
+        pyo3::Python::with_gil(|py| {
+            let text = match object.as_ref(py).extract::<&str>() {
+                Ok(text) => text,
+                Err(_) => return Ok(object.clone_ref(py)),
+            };
+            let chars = text.chars().count();
+            if chars <= max_length.chars {
+                return Ok(object.clone_ref(py));
+            }
+            if !max_length.truncate {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "<{}> is {} characters, over max_length {}",
+                    tag_name, chars, max_length.chars
+                )));
+            }
+            let truncated: String = text.chars().take(max_length.chars).collect();
+            self.truncated_paths.push((path.to_string(), chars));
+            Ok(string_to_py(py, &truncated))
+        })
+    }
+
+    /// `(path, original length)` of every string cut down to its
+    /// `MaxLength`, in the order they closed.
+    pub fn truncated_paths(&self) -> &[(String, usize)] {
+        &self.truncated_paths
+    }
+
+    /// Errors that `with_on_error` recovered from, in stream order.
+    pub fn issues(&self) -> &[ParseIssue] {
+        &self.issues
+    }
+
+    /// Paths where an `empty_as` policy replaced an empty tag, with the
+    /// policy applied.
+    pub fn empty_paths(&self) -> &[(String, EmptyAs)] {
+        &self.empty_paths
+    }
+
+    fn record_delta(&mut self, appended: &str) {
+        if self.string_deltas && !appended.is_empty() {
+            let path = self.current_path();
+            self.deltas.push((path, appended.to_string()));
+        }
+    }
+
+    /// Root values completed so far, labeled with the tag that produced them.
+    pub fn results(&self) -> &[(String, PyObject)] {
+        &self.results
+    }
+
+    /// Metadata tags that have been closed so far, keyed by configured name.
+    pub fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    /// Record metadata tag contents and return the events that belong to the
+    /// main payload.
+    fn take_metadata_events(&mut self, events: Vec<(usize, TagEvent)>) -> Vec<(usize, TagEvent)> {
+        if self.metadata_tags.is_empty() {
+            return events;
+        }
+
+        let mut remaining = Vec::with_capacity(events.len());
+        for (offset, event) in events {
+            if let Some((name, depth, text)) = self.current_metadata.as_mut() {
+                match event {
+                    TagEvent::Bytes(content) => text.push_str(&content),
+                    TagEvent::Close(_, close_depth) if close_depth == *depth => {
+                        debug!("Captured metadata tag '{}'", name);
+                        self.metadata.insert(name.clone(), text.trim().to_string());
+                        self.current_metadata = None;
+                    }
+                    // Tags nested inside metadata are not part of the payload
+                    _ => {}
+                }
+                continue;
+            }
+
+            if let TagEvent::Open(tag) = &event {
+                if self.stack.is_empty() {
+                    if let Some(name) = self.metadata_tags.get(&tag.name.to_lowercase()) {
+                        self.current_metadata = Some((name.clone(), tag.depth, String::new()));
+                        continue;
+                    }
+                }
+            }
+            remaining.push((offset, event));
+        }
+        remaining
     }
 
     fn should_use_stack(&self) -> bool {
@@ -125,30 +1504,42 @@ impl TypedStreamParser {
                 StackFrame::Field {
                     content, type_info, ..
                 } => {
-                    // Convert content to the appropriate primitive type
+                    // Convert content to the appropriate primitive type. Numbers
+                    // and bools may be padded with whitespace.
+                    let trimmed = content.trim();
                     match type_info.kind {
                         crate::python_types::PyTypeKind::String => {
-                            // Decode HTML entities for strings
-                            let decoded = content
-                                .replace("&amp;", "&")
-                                .replace("&lt;", "<")
-                                .replace("&gt;", ">")
-                                .replace("&quot;", "\"")
-                                .replace("&#39;", "'")
-                                .replace("&apos;", "'");
-                            Ok(decoded.into_py(py))
+                            let decoded = decode_entities(&content);
+                            Ok(string_to_py(py, &decoded))
                         }
-                        crate::python_types::PyTypeKind::Integer => match content.parse::<i64>() {
+                        crate::python_types::PyTypeKind::Integer => match trimmed.parse::<i64>() {
                             Ok(val) => Ok(val.into_py(py)),
+                            Err(_) if is_integer_literal(trimmed) => {
+                                // Too large for i64; Python ints have no limit
+                                Ok(py
+                                    .get_type::<pyo3::types::PyLong>()
+                                    .call1((trimmed,))?
+                                    .into())
+                            }
+                            Err(_) if self.python_numbers => {
+                                Ok(python_number(py, trimmed, false)?.unwrap_or_else(|| py.None()))
+                            }
                             Err(_) => Ok(py.None()),
                         },
-                        crate::python_types::PyTypeKind::Float => match content.parse::<f64>() {
+                        crate::python_types::PyTypeKind::Float => match trimmed.parse::<f64>() {
+                            Ok(_) if self.decimal_floats => {
+                                let decimal = py.import("decimal")?.getattr("Decimal")?;
+                                Ok(decimal.call1((trimmed,))?.into())
+                            }
                             Ok(val) => Ok(val.into_py(py)),
+                            Err(_) if self.python_numbers => {
+                                Ok(python_number(py, trimmed, true)?.unwrap_or_else(|| py.None()))
+                            }
                             Err(_) => Ok(py.None()),
                         },
                         crate::python_types::PyTypeKind::Boolean => {
                             let val =
-                                matches!(content.to_lowercase().as_str(), "true" | "1" | "yes");
+                                matches!(trimmed.to_lowercase().as_str(), "true" | "1" | "yes");
                             Ok(val.into_py(py))
                         }
                         crate::python_types::PyTypeKind::None => Ok(py.None()),
@@ -218,6 +1609,7 @@ impl TypedStreamParser {
                     key_type,
                     value_type,
                     current_key: None,
+                    collected: HashSet::new(),
                     depth,
                 }))
             }
@@ -264,6 +1656,8 @@ impl TypedStreamParser {
                     type_info: type_info.clone(),
                     instance: instance.into(),
                     current_field: None,
+                    seen_fields: HashSet::new(),
+                    collected: HashSet::new(),
                     depth,
                 }))
             }
@@ -313,7 +1707,7 @@ impl TypedStreamParser {
 
         while temp_stack.len() > 1 {
             let top_frame = temp_stack.pop().unwrap();
-            let py_object = self.frame_to_pyobject(top_frame)?;
+            let py_object = self.snapshot_object(top_frame)?;
 
             if let Some(parent_frame) = temp_stack.last_mut() {
                 match parent_frame {
@@ -336,7 +1730,29 @@ impl TypedStreamParser {
             }
         }
 
-        self.frame_to_pyobject(temp_stack[0].clone()).map(Some)
+        self.snapshot_object(temp_stack[0].clone()).map(Some)
+    }
+
+    /// Like `frame_to_pyobject`, for a frame that is still open: a string
+    /// over a truncating `MaxLength` is cut as it will be when it closes, but
+    /// not recorded in `truncated_paths` yet.
+    fn snapshot_object(&self, frame: StackFrame) -> PyResult<PyObject> {
+        let max_length = match &frame {
+            StackFrame::Field { type_info, .. } => type_info.max_length.filter(|max| max.truncate),
+            _ => None,
+        };
+        let object = self.frame_to_pyobject(frame)?;
+        let max_length = match max_length {
+            Some(max_length) => max_length,
+            None => return Ok(object),
+        };
+        pyo3::Python::with_gil(|py| match object.as_ref(py).extract::<&str>() {
+            Ok(text) if text.chars().count() > max_length.chars => {
+                let truncated: String = text.chars().take(max_length.chars).collect();
+                Ok(string_to_py(py, &truncated))
+            }
+            _ => Ok(object.clone_ref(py)),
+        })
     }
 
     fn is_inside_container(&self) -> bool {
@@ -362,26 +1778,8 @@ impl TypedStreamParser {
             self.stack.len()
         );
 
-        // Debug: print current stack state
-        for (i, frame) in self.stack.iter().enumerate() {
-            match frame {
-                StackFrame::Object {
-                    type_info,
-                    current_field,
-                    ..
-                } => {
-                    debug!(
-                        "  Stack[{}]: Object(type={}, current_field={:?})",
-                        i, type_info.name, current_field
-                    );
-                }
-                StackFrame::Field { name, .. } => {
-                    debug!("  Stack[{}]: Field(name={})", i, name);
-                }
-                _ => {
-                    debug!("  Stack[{}]: {:?}", i, frame);
-                }
-            }
+        if self.resolve_pending_union(tag)? {
+            return Ok(());
         }
 
         // Determine what type of frame to create based on the current stack top.
@@ -608,6 +2006,11 @@ impl TypedStreamParser {
                         depth: tag.depth,
                     });
                     pushed_new_frame = true;
+                } else if actual_type.kind == crate::python_types::PyTypeKind::Union
+                    && !actual_type.args.is_empty()
+                {
+                    // No type attribute or member tag name; let the first child decide
+                    self.pending_union = Some((tag.clone(), actual_type, None));
                 } else {
                     self.push_frame_for_type(&actual_type, tag_name, tag.depth)?;
                     pushed_new_frame = true;
@@ -617,38 +2020,127 @@ impl TypedStreamParser {
 
         // If we pushed a new frame, we need to update the parent frame's context
         if pushed_new_frame && self.stack.len() > 1 {
-            // Look at the frame right before the one we just pushed (the parent)
-            let parent_idx = self.stack.len() - 2;
-            match self.stack.get_mut(parent_idx) {
-                Some(StackFrame::Object {
-                    current_field,
-                    type_info,
-                    ..
-                }) => {
-                    // Only set current_field if this tag corresponds to a field of the object
-                    if type_info.fields.contains_key(tag_name) {
-                        debug!(
-                            "Setting current_field '{}' on Object frame at index {}",
-                            tag_name, parent_idx
-                        );
-                        *current_field = Some(tag_name.clone());
-                    }
-                }
-                Some(StackFrame::Dict { current_key, .. }) if tag_name == "item" => {
-                    // For dict items, store the key from the tag attributes
-                    if let Some(key_attr) = tag.attributes.get("key") {
-                        pyo3::Python::with_gil(|py| {
-                            *current_key = Some(key_attr.clone().into_py(py));
-                        });
-                    }
-                }
-                _ => {}
+            self.note_child_frame(tag);
+        }
+
+        Ok(())
+    }
+
+    /// Pick the member of a pending union from its first child tag: a
+    /// `<_type>Dog</_type>` key, a tag named after a member (`<pet><Dog>`),
+    /// or a field that only some members have (`<pet><barks>`), in which
+    /// case the first such member is used. Returns true if `tag` itself was
+    /// consumed as the choice.
+    fn resolve_pending_union(&mut self, tag: &Tag) -> PyResult<bool> {
+        let (union_tag, union, type_key) = match self.pending_union.take() {
+            Some(pending) if tag.depth == pending.0.depth + 1 => pending,
+            _ => return Ok(false),
+        };
+        if type_key.is_some() || tag.name == "_type" {
+            // Read the key's text; markup inside it is dropped
+            self.pending_union = Some((union_tag, union, type_key.or_else(|| Some(String::new()))));
+            return Ok(true);
+        }
+        let (member, consumed) = match union
+            .args
+            .iter()
+            .find(|member| member.name.eq_ignore_ascii_case(&tag.name))
+        {
+            Some(member) => (member, true),
+            None => match union
+                .args
+                .iter()
+                .find(|member| member.fields.contains_key(&tag.name))
+            {
+                Some(member) => (member, false),
+                None => return Ok(false),
+            },
+        };
+        if member.is_primitive() {
+            return Ok(false);
+        }
+        debug!(
+            "Union <{}> resolved to {} by child <{}>",
+            union_tag.name, member.name, tag.name
+        );
+        let member = member.clone();
+        self.push_union_member(&union_tag, &member)?;
+        if !consumed {
+            self.handle_stack_tag_open(tag)?;
+        }
+        Ok(true)
+    }
+
+    /// Called at `</_type>`: push the member the key names.
+    fn resolve_union_type_key(&mut self) -> PyResult<()> {
+        let (union_tag, union, key) = match self.pending_union.take() {
+            Some((tag, union, Some(key))) => (tag, union, key),
+            pending => {
+                self.pending_union = pending;
+                return Ok(());
             }
+        };
+        let key = key.trim();
+        let member = union
+            .args
+            .iter()
+            .find(|member| member.name.eq_ignore_ascii_case(key))
+            .ok_or_else(|| {
+                let names: Vec<&str> = union.args.iter().map(|m| m.name.as_str()).collect();
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "<{}> has _type {:?}, which is not one of {}",
+                    union_tag.name,
+                    key,
+                    names.join(", ")
+                ))
+            })?
+            .clone();
+        if member.is_primitive() {
+            return Ok(());
         }
+        self.push_union_member(&union_tag, &member)
+    }
 
+    fn push_union_member(&mut self, union_tag: &Tag, member: &PyTypeInfo) -> PyResult<()> {
+        let open_frames = self.stack.len();
+        self.push_frame_for_type(member, &union_tag.name, union_tag.depth)?;
+        if self.stack.len() > open_frames && open_frames > 0 {
+            self.note_child_frame(union_tag);
+        }
         Ok(())
     }
 
+    /// Point the frame under a newly pushed one at it: the object field it
+    /// fills, or the dict key it is stored under.
+    fn note_child_frame(&mut self, tag: &Tag) {
+        let tag_name = &tag.name;
+        // Look at the frame right before the one we just pushed (the parent)
+        let parent_idx = self.stack.len() - 2;
+        match self.stack.get_mut(parent_idx) {
+            // Only set current_field if this tag corresponds to a field of the object
+            Some(StackFrame::Object {
+                current_field,
+                type_info,
+                ..
+            }) if type_info.fields.contains_key(tag_name) => {
+                debug!(
+                    "Setting current_field '{}' on Object frame at index {}",
+                    tag_name, parent_idx
+                );
+                *current_field = Some(tag_name.clone());
+            }
+            Some(StackFrame::Dict { current_key, .. }) if tag_name == "item" => {
+                // For dict items, store the key from the tag attributes
+                if let Some(key_attr) = tag.attributes.get("key") {
+                    pyo3::Python::with_gil(|py| {
+                        *current_key = Some(key_attr.clone().into_py(py));
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn handle_stack_tag_close(&mut self, tag_name: &str, depth: usize) -> PyResult<()> {
         debug!(
             "handle_stack_tag_close: tag_name={}, depth={}, stack_len={}",
@@ -656,106 +2148,41 @@ impl TypedStreamParser {
             depth,
             self.stack.len()
         );
-        if let Some(top) = self.stack.last() {
-            debug!("  top_frame: {:?}", top);
+        match &self.pending_union {
+            Some((tag, _, Some(_))) if tag.depth + 1 == depth && tag_name == "_type" => {
+                return self.resolve_union_type_key();
+            }
+            // Closed before any child said which member it was
+            Some((tag, _, _)) if tag.depth >= depth => self.pending_union = None,
+            _ => {}
         }
 
         while let Some(top_frame) = self.stack.last() {
-            let (frame_tag_name, frame_depth) = match top_frame {
-                StackFrame::List {
-                    tag_name, depth, ..
-                } => (tag_name.clone(), *depth),
-                StackFrame::Dict {
-                    tag_name, depth, ..
-                } => (tag_name.clone(), *depth),
-                StackFrame::Set {
-                    tag_name, depth, ..
-                } => (tag_name.clone(), *depth),
-                StackFrame::Tuple {
-                    tag_name, depth, ..
-                } => (tag_name.clone(), *depth),
-                StackFrame::Object {
-                    tag_name, depth, ..
-                } => (tag_name.clone(), *depth),
-                StackFrame::Field { name, depth, .. } => (name.clone(), *depth),
-            };
+            let (frame_tag_name, frame_depth) = top_frame.tag_name_and_depth();
 
             if frame_depth > depth {
                 // This is a child of the current closing tag, which was not properly closed.
                 // We should pop it off and integrate it into its parent.
-                let child_frame = self.stack.pop().unwrap();
-                let child_object = self.frame_to_pyobject(child_frame)?;
+                let child_object = self.pop_frame_object()?;
 
                 if let Some(parent_frame) = self.stack.last_mut() {
-                    match parent_frame {
-                        StackFrame::List { items, .. } => items.push(child_object),
-                        StackFrame::Set { items, .. } => items.push(child_object),
-                        StackFrame::Tuple { items, .. } => items.push(child_object),
-                        StackFrame::Dict {
-                            entries,
-                            current_key,
-                            ..
-                        } => {
-                            if let Some(key) = current_key.take() {
-                                entries.push((key, child_object));
-                            }
-                        }
-                        StackFrame::Object {
-                            instance,
-                            current_field,
-                            ..
-                        } => {
-                            if let Some(field_name) = current_field.take() {
-                                pyo3::Python::with_gil(|py| {
-                                    let _ = instance
-                                        .as_ref(py)
-                                        .setattr(field_name.as_str(), child_object);
-                                });
-                            }
-                        }
-                        _ => {}
-                    }
+                    parent_frame.attach_child(child_object, self.duplicate_keys)?;
                 }
             } else if frame_depth == depth
                 && frame_tag_name.to_lowercase() == tag_name.to_lowercase()
-            {
-                // This is the matching frame for the closing tag.
-                let child_frame = self.stack.pop().unwrap();
-                let child_object = self.frame_to_pyobject(child_frame)?;
-
-                if let Some(parent_frame) = self.stack.last_mut() {
-                    match parent_frame {
-                        StackFrame::List { items, .. } => items.push(child_object),
-                        StackFrame::Set { items, .. } => items.push(child_object),
-                        StackFrame::Tuple { items, .. } => items.push(child_object),
-                        StackFrame::Dict {
-                            entries,
-                            current_key,
-                            ..
-                        } => {
-                            if let Some(key) = current_key.take() {
-                                entries.push((key, child_object));
-                            }
-                        }
-                        StackFrame::Object {
-                            instance,
-                            current_field,
-                            ..
-                        } => {
-                            if let Some(field_name) = current_field.take() {
-                                pyo3::Python::with_gil(|py| {
-                                    let _ = instance
-                                        .as_ref(py)
-                                        .setattr(field_name.as_str(), child_object);
-                                });
-                            }
-                        }
-                        _ => {}
+            {
+                // This is the matching frame for the closing tag.
+                let root_tag = frame_tag_name.to_string();
+                let child_object = self.pop_closed_frame_object()?;
+
+                if let Some(parent_frame) = self.stack.last_mut() {
+                    parent_frame.attach_child(child_object, self.duplicate_keys)?;
+                    if self.eager_complete {
+                        self.complete_root_if_filled()?;
                     }
                 } else {
                     // No parent, this is the root object.
-                    self.stack_based_result = Some(child_object);
-                    self.is_done = true;
+                    self.complete_root(root_tag, child_object);
                 }
                 break; // We've handled the closing tag, so we can exit the loop.
             } else {
@@ -767,59 +2194,215 @@ impl TypedStreamParser {
         Ok(())
     }
 
+    /// Record a completed root value. In multiple mode the parser keeps going
+    /// and waits for the next root tag.
+    fn complete_root(&mut self, tag_name: String, value: PyObject) {
+        self.stack_based_result = Some(value.clone());
+        self.last_root_tag = Some(tag_name.clone());
+        if self.multiple {
+            self.results.push((tag_name, value));
+        } else {
+            self.is_done = true;
+        }
+    }
+
+    /// Finish early when the only open frame is a class root whose declared
+    /// fields have all been set.
+    fn complete_root_if_filled(&mut self) -> PyResult<()> {
+        let filled = match self.stack.as_slice() {
+            [StackFrame::Object {
+                type_info,
+                seen_fields,
+                ..
+            }] => {
+                !type_info.fields.is_empty()
+                    && type_info.fields.keys().all(|f| seen_fields.contains(f))
+            }
+            _ => false,
+        };
+        if filled {
+            debug!("All root fields closed; completing before the root close tag");
+            let root_tag = self.stack[0].tag_name_and_depth().0.to_string();
+            let object = self.pop_frame_object()?;
+            self.complete_root(root_tag, object);
+        }
+        Ok(())
+    }
+
     fn handle_stack_bytes(&mut self, content: &str) -> PyResult<()> {
-        if let Some(StackFrame::Field {
-            content: field_content,
-            ..
-        }) = self.stack.last_mut()
-        {
-            field_content.push_str(content);
+        if let Some((_, _, Some(key))) = self.pending_union.as_mut() {
+            key.push_str(content);
+            return Ok(());
+        }
+        let top = self.stack.len().saturating_sub(1);
+        match self.stack.last_mut() {
+            Some(StackFrame::Field {
+                content: field_content,
+                name,
+                type_info,
+                ..
+            }) => {
+                field_content.push_str(content);
+                let mismatch = (self.strict_types
+                    && cannot_become(&type_info.kind, field_content, self.python_numbers))
+                .then(|| type_mismatch(name, field_content, type_info));
+                self.record_delta(content);
+                if let Some(err) = mismatch {
+                    return Err(err);
+                }
+            }
+            Some(
+                frame @ (StackFrame::List { .. }
+                | StackFrame::Set { .. }
+                | StackFrame::Tuple { .. }),
+            ) if self.python_literals => {
+                // Possibly a `(1, 2)`-style literal; read when the tag closes
+                let at = (top, frame.tag_name_and_depth().1);
+                match &mut self.literal_text {
+                    Some((idx, depth, text)) if (*idx, *depth) == at => text.push_str(content),
+                    _ if !content.trim().is_empty() => {
+                        self.literal_text = Some((at.0, at.1, content.to_string()))
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
         }
         Ok(())
     }
 
     pub fn step(&mut self, chunk: &str) -> PyResult<Option<PyObject>> {
-        let mut events = Vec::new();
-        let events_ref = &mut events;
-        self.tag_finder
-            .push(chunk, |event| {
-                debug!("Callback received event: {:?}", event);
-                events_ref.push(event);
-                Ok(())
-            })
-            .map_err(|e| {
-                pyo3::exceptions::PyValueError::new_err(format!("Tag parsing error: {:?}", e))
-            })?;
+        if self.coalesce_bytes == 0 {
+            return self.step_now(chunk);
+        }
+        self.pending.push_str(chunk);
+        if self.pending.len() < self.coalesce_bytes && !chunk.contains('>') {
+            return Ok(self.last_step.clone());
+        }
+        self.flush_pending()
+    }
+
+    /// Parse the chunks held back by coalescing.
+    fn flush_pending(&mut self) -> PyResult<Option<PyObject>> {
+        let pending = std::mem::take(&mut self.pending);
+        let value = self.step_now(&pending)?;
+        self.last_step = value.clone();
+        Ok(value)
+    }
+
+    fn step_now(&mut self, chunk: &str) -> PyResult<Option<PyObject>> {
+        if self.limit_error.is_some() {
+            self.enforce_limits()?;
+        }
+        #[cfg(feature = "metrics")]
+        let started = self.clock.now();
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "step",
+            bytes = chunk.len(),
+            offset = self.stats.bytes,
+            events = tracing::field::Empty,
+        )
+        .entered();
+        self.tag_finder.feed(chunk).map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!("Tag parsing error: {:?}", e))
+        })?;
+        let events: Vec<(usize, TagEvent)> = self.tag_finder.events().collect();
+
+        let event_count = events.len();
+        #[cfg(feature = "tracing")]
+        span.record("events", event_count);
+        let start = self.stats.bytes as usize;
+        self.line_starts
+            .extend(chunk.match_indices('\n').map(|(idx, _)| start + idx + 1));
+        self.stats.bytes += chunk.len() as u64;
+        self.stats.events += event_count as u64;
+        let result = self.process_events(events).and_then(|value| {
+            self.enforce_limits()?;
+            Ok(value)
+        });
+        #[cfg(feature = "metrics")]
+        self.record_step(StepSample {
+            started,
+            bytes: chunk.len(),
+            events: event_count,
+            latency: self.clock.now() - started,
+        });
+        result
+    }
 
-        debug!("step: chunk={:?}, collected events={:?}", chunk, events);
+    /// Like `step`, for raw bytes. A multi-byte character split across
+    /// chunks is held back until the rest of it arrives; invalid bytes are
+    /// replaced with U+FFFD.
+    pub fn step_bytes(&mut self, chunk: &[u8]) -> PyResult<Option<PyObject>> {
+        let text = decode_utf8_chunk(&mut self.utf8_carry, chunk);
+        self.step(&text)
+    }
+
+    fn process_events(&mut self, events: Vec<(usize, TagEvent)>) -> PyResult<Option<PyObject>> {
+        let events = self.take_metadata_events(events);
+        if self.is_done {
+            // Only the first root value is parsed unless `multiple` is set
+            return Ok(self.stack_based_result.clone());
+        }
 
         if self.should_use_stack() {
-            for event in &events {
-                match event {
+            for (offset, event) in &events {
+                if self.is_done {
+                    break;
+                }
+                if let Some(skip_depth) = self.skip_depth {
+                    match event {
+                        // An outer close also ends the failed element
+                        crate::tag_finder::TagEvent::Close(_, depth) if *depth < skip_depth => {
+                            self.skip_depth = None
+                        }
+                        crate::tag_finder::TagEvent::Close(_, depth) if *depth == skip_depth => {
+                            self.skip_depth = None;
+                            continue;
+                        }
+                        _ => continue,
+                    }
+                }
+                let open_frames = self.stack.len();
+                let handled = match event {
                     crate::tag_finder::TagEvent::Open(tag) => {
                         self.depth = tag.depth;
-                        self.handle_stack_tag_open(tag)?
+                        self.nodes += 1;
+                        self.handle_stack_tag_open(tag)
                     }
                     crate::tag_finder::TagEvent::Close(name, depth) => {
                         self.depth = *depth;
-                        self.handle_stack_tag_close(name, *depth)?
-                    }
-                    crate::tag_finder::TagEvent::Bytes(content) => {
-                        self.handle_stack_bytes(content)?
+                        self.handle_stack_tag_close(name, *depth)
                     }
+                    crate::tag_finder::TagEvent::Bytes(content) => self.handle_stack_bytes(content),
+                };
+                if let Err(err) = handled {
+                    self.recover(err, *offset, open_frames, event)
+                        .map_err(|err| self.locate_error(err, *offset))?;
                 }
+                self.stats.peak_depth = self.stats.peak_depth.max(self.stack.len());
+                self.enforce_limits()?;
             }
-            if self.is_done {
+            if self.is_done || (self.multiple && self.stack.is_empty()) {
                 return Ok(self.stack_based_result.clone());
             }
+            self.stats.snapshots += 1;
             return self.build_current_intermediate_state();
         }
 
+        // Tags under a primitive or untyped root count toward max_nodes too
+        self.nodes += events
+            .iter()
+            .filter(|(_, event)| matches!(event, TagEvent::Open(_)))
+            .count();
+        self.enforce_limits()?;
+
         // Handle primitive types that don't use the stack
-        if let Some(type_info) = &self.type_info {
+        if let Some(type_info) = self.type_info.clone() {
             if type_info.is_primitive() {
                 // For primitive types, we need a simple tag + content structure
-                for event in &events {
+                for (offset, event) in &events {
                     match event {
                         crate::tag_finder::TagEvent::Open(tag) => {
                             if tag.name.to_lowercase() == type_info.name.to_lowercase()
@@ -837,10 +2420,25 @@ impl TypedStreamParser {
                         crate::tag_finder::TagEvent::Bytes(content) => {
                             if let Some(StackFrame::Field {
                                 content: field_content,
+                                name,
+                                type_info,
                                 ..
                             }) = self.stack.last_mut()
                             {
                                 field_content.push_str(content);
+                                if self.string_deltas && !content.is_empty() {
+                                    self.deltas.push((name.clone(), content.clone()));
+                                }
+                                if self.strict_types
+                                    && cannot_become(
+                                        &type_info.kind,
+                                        field_content,
+                                        self.python_numbers,
+                                    )
+                                {
+                                    let err = type_mismatch(name, field_content, type_info);
+                                    return Err(self.locate_error(err, *offset));
+                                }
                             }
                         }
                         crate::tag_finder::TagEvent::Close(name, _) => {
@@ -848,10 +2446,23 @@ impl TypedStreamParser {
                                 && !self.stack.is_empty()
                             {
                                 if let Some(frame) = self.stack.pop() {
-                                    let result = self.frame_to_pyobject(frame)?;
-                                    self.stack_based_result = Some(result.clone());
-                                    self.is_done = true;
-                                    return Ok(Some(result));
+                                    let empty = self
+                                        .empty_value(&frame)
+                                        .map_err(|err| self.locate_error(err, *offset))?;
+                                    let result = match empty {
+                                        Some((policy, object)) => {
+                                            self.empty_paths.push((name.clone(), policy));
+                                            object
+                                        }
+                                        None => self
+                                            .checked_field_object(frame, name)
+                                            .map_err(|err| self.locate_error(err, *offset))?,
+                                    };
+                                    self.notify_subscribers(name, &result)?;
+                                    self.complete_root(name.clone(), result.clone());
+                                    if !self.multiple {
+                                        return Ok(Some(result));
+                                    }
                                 }
                             }
                         }
@@ -860,6 +2471,8 @@ impl TypedStreamParser {
 
                 // Return partial results for primitives
                 if !self.stack.is_empty() {
+                    self.stats.peak_depth = self.stats.peak_depth.max(1);
+                    self.stats.snapshots += 1;
                     if let Some(StackFrame::Field {
                         content,
                         type_info,
@@ -868,7 +2481,7 @@ impl TypedStreamParser {
                     }) = self.stack.last()
                     {
                         // Build a partial result from the current content
-                        let partial = self.frame_to_pyobject(StackFrame::Field {
+                        let partial = self.snapshot_object(StackFrame::Field {
                             name: type_info.name.clone(),
                             content: content.clone(),
                             type_info: type_info.clone(),
@@ -886,123 +2499,794 @@ impl TypedStreamParser {
     pub fn is_done(&self) -> bool {
         self.is_done
     }
+
+    /// Like `step`, but labels the value with the root tag it came from and
+    /// whether that root has closed.
+    pub fn step_tagged(&mut self, chunk: &str) -> PyResult<Option<TaggedValue>> {
+        let value = match self.step(chunk)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        // An open root frame means the value is still being built
+        let (tag, complete) = match self.stack.first() {
+            Some(frame) => (Some(frame.tag_name_and_depth().0.to_string()), false),
+            None => (self.last_root_tag.clone(), true),
+        };
+        Ok(tag.map(|tag| TaggedValue {
+            tag,
+            value,
+            complete,
+        }))
+    }
+
+    /// Signal that the model has stopped. Tags still open are closed as if
+    /// their closing tags had arrived, the parser is marked done, and the
+    /// best-effort final value is returned.
+    pub fn finish(&mut self) -> PyResult<Option<PyObject>> {
+        if !self.pending.is_empty() {
+            self.flush_pending()?;
+        }
+        if !self.utf8_carry.is_empty() {
+            debug!(
+                "finish: dropped incomplete UTF-8 {:?}",
+                Redacted(&self.utf8_carry)
+            );
+            self.utf8_carry.clear();
+        }
+        let dropped = self.tag_finder.finish();
+        if !dropped.is_empty() {
+            debug!("finish: dropped incomplete input {:?}", Redacted(&dropped));
+        }
+        if self.is_done {
+            return Ok(self.stack_based_result.clone());
+        }
+
+        self.unclosed_tags = self
+            .stack
+            .iter()
+            .map(|frame| frame.tag_name_and_depth().0.to_string())
+            .collect();
+        self.unclosed_paths = self.frame_paths();
+        while let Some(frame) = self.stack.last() {
+            let root_tag = frame.tag_name_and_depth().0.to_string();
+            let object = self.pop_frame_object()?;
+            match self.stack.last_mut() {
+                Some(parent_frame) => parent_frame.attach_child(object, self.duplicate_keys)?,
+                None => self.complete_root(root_tag, object),
+            }
+        }
+
+        self.is_done = true;
+        Ok(self.stack_based_result.clone())
+    }
+
+    /// Get ready for a new stream: drop everything parsed so far but keep
+    /// the type, options and subscriptions, and the capacity of internal
+    /// buffers. Cheaper than building a new parser for every generation.
+    pub fn reset(&mut self) {
+        self.tag_finder.reset();
+        self.is_done = false;
+        self.stack.clear();
+        self.stack_based_result = None;
+        self.depth = 0;
+        self.unclosed_tags.clear();
+        self.unclosed_paths.clear();
+        self.results.clear();
+        self.last_root_tag = None;
+        self.metadata.clear();
+        self.current_metadata = None;
+        self.deltas.clear();
+        self.literal_text = None;
+        self.utf8_carry.clear();
+        self.pending.clear();
+        self.last_step = None;
+        self.empty_paths.clear();
+        self.truncated_paths.clear();
+        self.issues.clear();
+        self.skip_depth = None;
+        self.pending_union = None;
+        self.nodes = 0;
+        self.limit_error = None;
+        self.stats = ParserStats::default();
+        self.line_starts.clear();
+    }
+
+    /// Send an additional copy of every step sample to `recorder`.
+    #[cfg(feature = "metrics")]
+    pub fn set_metrics_recorder(&mut self, recorder: Box<dyn MetricsRecorder>) {
+        self.recorder = Some(recorder);
+    }
+
+    /// Take step timings from `clock` instead of the system clock, e.g. a
+    /// fake clock for deterministic tests and replays.
+    #[cfg(feature = "metrics")]
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Rolling throughput and latency of `step` calls so far.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> MetricsSnapshot {
+        self.metrics.snapshot()
+    }
+
+    #[cfg(feature = "metrics")]
+    fn record_step(&mut self, sample: StepSample) {
+        self.metrics.record(sample);
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.record(sample);
+        }
+    }
+
+    /// Input held by the parser that has not become part of a value yet:
+    /// split tags, partial UTF-8, chunks held by coalescing, and the text of
+    /// fields still open.
+    pub fn buffered_bytes(&self) -> usize {
+        let fields: usize = self
+            .stack
+            .iter()
+            .map(|frame| match frame {
+                StackFrame::Field { content, .. } => content.len(),
+                _ => 0,
+            })
+            .sum();
+        let metadata = self
+            .current_metadata
+            .as_ref()
+            .map_or(0, |(_, _, text)| text.len());
+        self.tag_finder.buffered_len()
+            + self.utf8_carry.len()
+            + self.pending.len()
+            + fields
+            + metadata
+    }
+
+    /// Counters since the parser was created.
+    pub fn stats(&self) -> ParserStats {
+        self.stats
+    }
+
+    /// Tags, outermost first, that were still open when `finish` was called.
+    pub fn unclosed_tags(&self) -> &[String] {
+        &self.unclosed_tags
+    }
+
+    /// Paths (e.g. `Person.hobbies[1]`) of the values left open when
+    /// `finish` was called, outermost first.
+    pub fn unclosed_paths(&self) -> &[String] {
+        &self.unclosed_paths
+    }
+}
+
+impl Drop for TypedStreamParser {
+    fn drop(&mut self) {
+        let buffered = self.buffered_bytes();
+        if buffered > 0 {
+            debug!(
+                "TypedStreamParser dropped mid-stream with {} unconsumed buffered bytes",
+                buffered
+            );
+        }
+    }
+}
+
+/// Forwards step samples to a Python callable `(bytes, events, latency_secs)`.
+#[cfg(feature = "metrics")]
+#[derive(Debug)]
+struct PyMetricsRecorder(PyObject);
+
+#[cfg(feature = "metrics")]
+impl MetricsRecorder for PyMetricsRecorder {
+    fn record(&mut self, sample: StepSample) {
+        pyo3::Python::with_gil(|py| {
+            let args = (sample.bytes, sample.events, sample.latency.as_secs_f64());
+            if let Err(err) = self.0.call1(py, args) {
+                // A broken recorder must not fail the parse
+                err.print(py);
+            }
+        });
+    }
+}
+
+/// Reads monotonic seconds from a Python callable, such as `time.monotonic`
+/// or a fake clock in tests.
+#[cfg(feature = "metrics")]
+#[derive(Debug)]
+struct PyClock {
+    now: PyObject,
+    base: std::time::Instant,
+    origin: f64, // reading of `now` taken at `base`
+}
+
+#[cfg(feature = "metrics")]
+impl PyClock {
+    fn new(now: PyObject) -> PyResult<Self> {
+        let origin = pyo3::Python::with_gil(|py| now.call0(py)?.extract::<f64>(py))?;
+        Ok(Self {
+            now,
+            base: std::time::Instant::now(),
+            origin,
+        })
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl Clock for PyClock {
+    fn now(&self) -> std::time::Instant {
+        pyo3::Python::with_gil(
+            |py| match self.now.call0(py).and_then(|t| t.extract::<f64>(py)) {
+                // Readings before the origin would need a negative offset
+                Ok(seconds) => {
+                    self.base + std::time::Duration::from_secs_f64((seconds - self.origin).max(0.0))
+                }
+                Err(err) => {
+                    // A broken clock must not fail the parse
+                    err.print(py);
+                    std::time::Instant::now()
+                }
+            },
+        )
+    }
+}
+
+/// Keyword options shared by `Parser()` and `Parser.from_pydantic`, so the
+/// two can't drift apart.
+struct ParserOptions {
+    ignored_tags: Vec<String>,
+    metadata_tags: Vec<String>,
+    eager_complete: bool,
+    multiple: bool,
+    string_deltas: bool,
+    decimal_floats: bool,
+    passthrough_text: bool,
+    empty_as: HashMap<String, EmptyAs>,
+    duplicate_keys: DuplicateKeys,
+    limits: Limits,
+    trace_tags: bool,
+    on_error: OnError,
+    python_numbers: bool,
+    python_literals: bool,
+    coalesce_bytes: usize,
+    strict_types: bool,
+}
+
+impl ParserOptions {
+    /// Read `kwargs`, starting from the defaults with `ignored_tags` ignored.
+    /// Unknown names raise `TypeError`, as they would for a Python function.
+    fn from_kwargs(
+        function: &str,
+        kwargs: Option<&pyo3::types::PyDict>,
+        ignored_tags: Vec<String>,
+    ) -> PyResult<Self> {
+        let mut options = Self {
+            ignored_tags,
+            metadata_tags: Vec::new(),
+            eager_complete: false,
+            multiple: false,
+            string_deltas: false,
+            decimal_floats: false,
+            passthrough_text: false,
+            empty_as: HashMap::new(),
+            duplicate_keys: DuplicateKeys::default(),
+            limits: Limits::default(),
+            trace_tags: false,
+            on_error: OnError::default(),
+            python_numbers: false,
+            python_literals: false,
+            coalesce_bytes: 0,
+            strict_types: false,
+        };
+        for (key, value) in kwargs.into_iter().flatten() {
+            let key: &str = key.extract()?;
+            match key {
+                "ignored_tags" => options.ignored_tags = value.extract()?,
+                "metadata_tags" => options.metadata_tags = value.extract()?,
+                "eager_complete" => options.eager_complete = value.extract()?,
+                "multiple" => options.multiple = value.extract()?,
+                "string_deltas" => options.string_deltas = value.extract()?,
+                "decimal_floats" => options.decimal_floats = value.extract()?,
+                "passthrough_text" => options.passthrough_text = value.extract()?,
+                "empty_as" => options.empty_as = Self::empty_as(value.extract()?)?,
+                "duplicate_keys" => {
+                    let name: &str = value.extract()?;
+                    options.duplicate_keys = DuplicateKeys::from_name(name).ok_or_else(|| {
+                        pyo3::exceptions::PyValueError::new_err(format!(
+                            "Unknown duplicate_keys policy {:?}; expected \"last_wins\", \"first_wins\", \"error\" or \"collect\"",
+                            name
+                        ))
+                    })?;
+                }
+                "max_depth" => options.limits.max_depth = value.extract()?,
+                "max_string_length" => options.limits.max_string_length = value.extract()?,
+                "max_nodes" => options.limits.max_nodes = value.extract()?,
+                "max_buffer_bytes" => options.limits.max_buffer_bytes = value.extract()?,
+                "trace_tags" => options.trace_tags = value.extract()?,
+                "on_error" => {
+                    let name: &str = value.extract()?;
+                    options.on_error = OnError::from_name(name).ok_or_else(|| {
+                        pyo3::exceptions::PyValueError::new_err(format!(
+                            "Unknown on_error policy {:?}; expected \"raise\", \"skip\" or \"null\"",
+                            name
+                        ))
+                    })?;
+                }
+                "python_numbers" => options.python_numbers = value.extract()?,
+                "python_literals" => options.python_literals = value.extract()?,
+                "coalesce_bytes" => options.coalesce_bytes = value.extract()?,
+                "strict_types" => options.strict_types = value.extract()?,
+                _ => {
+                    return Err(pyo3::exceptions::PyTypeError::new_err(format!(
+                        "{}() got an unexpected keyword argument '{}'",
+                        function, key
+                    )))
+                }
+            }
+        }
+        Ok(options)
+    }
+
+    fn empty_as(names: HashMap<String, String>) -> PyResult<HashMap<String, EmptyAs>> {
+        names
+            .into_iter()
+            .map(|(tag, name)| match EmptyAs::from_name(&name) {
+                Some(policy) => Ok((tag, policy)),
+                None => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown empty_as policy {:?} for tag {:?}; expected \"null\", \"empty_object\", \"empty_string\" or \"error\"",
+                    name, tag
+                ))),
+            })
+            .collect()
+    }
+
+    /// The options given that only shape a typed value.
+    fn type_only(&self) -> Vec<&'static str> {
+        [
+            ("eager_complete", self.eager_complete),
+            ("multiple", self.multiple),
+            ("string_deltas", self.string_deltas),
+            ("decimal_floats", self.decimal_floats),
+            ("empty_as", !self.empty_as.is_empty()),
+            (
+                "duplicate_keys",
+                self.duplicate_keys != DuplicateKeys::default(),
+            ),
+            ("on_error", self.on_error != OnError::default()),
+            ("python_numbers", self.python_numbers),
+            ("python_literals", self.python_literals),
+            ("strict_types", self.strict_types),
+        ]
+        .into_iter()
+        .filter(|(_, given)| *given)
+        .map(|(name, _)| name)
+        .collect()
+    }
 }
 
 #[pyclass(name = "Parser", unsendable)]
-pub struct PyParser {
+pub(crate) struct PyParser {
     parser: TypedStreamParser,
     result: Option<PyObject>,
 }
 
-#[pymethods]
 impl PyParser {
-    #[new]
-    #[pyo3(signature = (type_obj=None, ignored_tags=vec!["think".to_string(), "thinking".to_string(), "system".to_string(), "thought".to_string()]))]
-    fn new(py: Python, type_obj: Option<&PyAny>, ignored_tags: Vec<String>) -> PyResult<Self> {
+    fn build(type_obj: Option<&PyAny>, options: ParserOptions) -> PyResult<Self> {
         debug!(
             "[PyParser::new] type_obj: {:?}",
             type_obj.map(|o| o
                 .repr()
-                .unwrap_or_else(|_| PyString::new(py, "Error getting repr").into()))
+                .unwrap_or_else(|_| PyString::new(o.py(), "Error getting repr")))
         );
-        match type_obj {
-            Some(obj) => {
-                let mut type_info = PyTypeInfo::extract_from_python(obj)?;
-                debug!(
-                    "[PyParser::new] Extracted type_info: name='{}', kind='{:?}', origin='{:?}'",
-                    type_info.name, type_info.kind, type_info.origin
-                );
-
-                if type_info.py_type.is_none() {
-                    type_info.py_type = Some(obj.into_py(py));
-                }
-
-                let wanted_tags = match type_info.kind {
-                    crate::python_types::PyTypeKind::Union => {
-                        // For unions, collect all member type names
-                        let mut tags: Vec<String> =
-                            type_info.args.iter().map(|arg| arg.name.clone()).collect();
-                        tags.push(type_info.name.clone());
-
-                        // Also add lowercase versions to handle case-insensitive matching
-                        let lowercase_tags: Vec<String> =
-                            tags.iter().map(|s| s.to_lowercase()).collect();
-                        tags.extend(lowercase_tags);
-                        tags.sort();
-                        tags.dedup();
-                        tags
-                    }
-                    _ => {
-                        // For non-union types, include both original and lowercase versions
-                        let mut tags = vec![type_info.name.clone()];
-                        let lowercase = type_info.name.to_lowercase();
-                        if lowercase != type_info.name {
-                            tags.push(lowercase);
-                        }
-                        tags
-                    }
-                };
-                debug!("[PyParser::new] wanted_tags: {:?}", wanted_tags);
-                let parser = TypedStreamParser::with_type(type_info, wanted_tags, ignored_tags);
-                Ok(Self {
-                    parser,
-                    result: None,
-                })
-            }
+        let obj = match type_obj {
+            Some(obj) => obj,
             None => {
+                // Without a type there is no value for these to shape
+                let given = options.type_only();
+                if !given.is_empty() {
+                    return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                        "{} can't be used without a type_obj",
+                        given.join(", ")
+                    )));
+                }
                 debug!("[PyParser::new] No type_obj provided.");
-                let parser = TypedStreamParser::new(Vec::new(), ignored_tags);
-                Ok(Self {
+                let parser = TypedStreamParser::new(Vec::new(), options.ignored_tags)
+                    .with_metadata_tags(options.metadata_tags)
+                    .with_passthrough_text(options.passthrough_text)
+                    .with_limits(options.limits)
+                    .with_tag_trace(options.trace_tags)
+                    .with_coalesce_bytes(options.coalesce_bytes);
+                return Ok(Self {
                     parser,
                     result: None,
-                })
+                });
             }
-        }
-    }
+        };
+        let mut type_info = PyTypeInfo::extract_from_python(obj)?;
+        debug!(
+            "[PyParser::new] Extracted type_info: name='{}', kind='{:?}', origin='{:?}'",
+            type_info.name, type_info.kind, type_info.origin
+        );
 
-    #[staticmethod]
-    #[pyo3(text_signature = "(pydantic_model)")]
-    fn from_pydantic(py: Python, pydantic_model: &PyAny) -> PyResult<Self> {
-        let mut type_info = PyTypeInfo::extract_from_python(pydantic_model)?;
         if type_info.py_type.is_none() {
-            type_info.py_type = Some(pydantic_model.into_py(py));
+            type_info.py_type = Some(obj.into_py(obj.py()));
         }
 
-        // Include both original and lowercase versions for case-insensitive matching
-        let mut wanted_tags = vec![type_info.name.clone()];
-        let lowercase = type_info.name.to_lowercase();
-        if lowercase != type_info.name {
-            wanted_tags.push(lowercase);
-        }
-        let typed_stream_parser = TypedStreamParser::with_type(type_info, wanted_tags, Vec::new());
+        let wanted_tags = match type_info.kind {
+            crate::python_types::PyTypeKind::Union => {
+                // For unions, collect all member type names
+                let mut tags: Vec<String> =
+                    type_info.args.iter().map(|arg| arg.name.clone()).collect();
+                tags.push(type_info.name.clone());
 
+                // Also add lowercase versions to handle case-insensitive matching
+                let lowercase_tags: Vec<String> = tags.iter().map(|s| s.to_lowercase()).collect();
+                tags.extend(lowercase_tags);
+                tags.sort();
+                tags.dedup();
+                tags
+            }
+            _ => {
+                // For non-union types, include both original and lowercase versions
+                let mut tags = vec![type_info.name.clone()];
+                let lowercase = type_info.name.to_lowercase();
+                if lowercase != type_info.name {
+                    tags.push(lowercase);
+                }
+                tags
+            }
+        };
+        debug!("[PyParser::new] wanted_tags: {:?}", wanted_tags);
+        let parser = TypedStreamParser::with_type(type_info, wanted_tags, options.ignored_tags)
+            .with_metadata_tags(options.metadata_tags)
+            .with_eager_complete(options.eager_complete)
+            .with_multiple(options.multiple)
+            .with_string_deltas(options.string_deltas)
+            .with_decimal_floats(options.decimal_floats)
+            .with_python_numbers(options.python_numbers)
+            .with_python_literals(options.python_literals)
+            .with_strict_types(options.strict_types)
+            .with_passthrough_text(options.passthrough_text)
+            .with_empty_as(options.empty_as)
+            .with_duplicate_keys(options.duplicate_keys)
+            .with_on_error(options.on_error)
+            .with_limits(options.limits)
+            .with_tag_trace(options.trace_tags)
+            .with_coalesce_bytes(options.coalesce_bytes);
         Ok(Self {
-            parser: typed_stream_parser,
+            parser,
             result: None,
         })
     }
+}
+
+#[pymethods]
+impl PyParser {
+    /// Takes `type_obj` and the keyword options listed in `gasp.pyi`.
+    #[new]
+    #[pyo3(signature = (type_obj=None, **options))]
+    fn new(type_obj: Option<&PyAny>, options: Option<&pyo3::types::PyDict>) -> PyResult<Self> {
+        let ignored_tags = ["think", "thinking", "system", "thought"]
+            .map(String::from)
+            .to_vec();
+        Self::build(
+            type_obj,
+            ParserOptions::from_kwargs("Parser", options, ignored_tags)?,
+        )
+    }
+
+    /// A parser for a Pydantic model. Takes the same options as `Parser`,
+    /// except that no tags are ignored unless `ignored_tags` is given.
+    #[staticmethod]
+    #[pyo3(signature = (pydantic_model, **options))]
+    fn from_pydantic(
+        pydantic_model: &PyAny,
+        options: Option<&pyo3::types::PyDict>,
+    ) -> PyResult<Self> {
+        Self::build(
+            Some(pydantic_model),
+            ParserOptions::from_kwargs("from_pydantic", options, Vec::new())?,
+        )
+    }
 
     #[pyo3(text_signature = "($self, chunk)")]
     fn feed(&mut self, _py: Python, chunk: &str) -> PyResult<Option<PyObject>> {
-        debug!("Feeding chunk: {}", chunk);
         if let Some(res) = self.parser.step(chunk)? {
             self.result = Some(res);
         }
         Ok(self.result.clone())
     }
 
+    /// Feed raw bytes, e.g. straight from an HTTP stream. Characters split
+    /// across chunks are reassembled; invalid UTF-8 becomes U+FFFD.
+    #[pyo3(text_signature = "($self, chunk)")]
+    fn feed_bytes(&mut self, chunk: &[u8]) -> PyResult<Option<PyObject>> {
+        if let Some(res) = self.parser.step_bytes(chunk)? {
+            self.result = Some(res);
+        }
+        Ok(self.result.clone())
+    }
+
     #[pyo3(text_signature = "($self)")]
     fn is_complete(&self) -> bool {
         self.parser.is_done()
     }
 
+    /// Feed a chunk; returns `(tag_name, value, complete)` for the current
+    /// value, or `None` if nothing has been parsed yet.
+    #[pyo3(text_signature = "($self, chunk)")]
+    fn feed_tagged(&mut self, chunk: &str) -> PyResult<Option<(String, PyObject, bool)>> {
+        let tagged = self.parser.step_tagged(chunk)?;
+        if let Some(tagged) = &tagged {
+            self.result = Some(tagged.value.clone());
+        }
+        Ok(tagged.map(|t| (t.tag, t.value, t.complete)))
+    }
+
+    /// Signal the end of the stream and return the best-effort final object.
+    /// With `strict`, raise `ValueError` naming every value left open instead.
+    #[pyo3(signature = (strict=false), text_signature = "($self, strict=False)")]
+    fn finish(&mut self, strict: bool) -> PyResult<Option<PyObject>> {
+        if let Some(res) = self.parser.finish()? {
+            self.result = Some(res);
+        }
+        let unclosed = self.parser.unclosed_paths();
+        if strict && !unclosed.is_empty() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Stream ended with {} unclosed value(s): {}",
+                unclosed.len(),
+                unclosed.join(", ")
+            )));
+        }
+        Ok(self.result.clone())
+    }
+
+    /// Start over for a new stream with the same type and options, e.g. one
+    /// parser per worker reused across requests. Subscriptions are kept.
+    #[pyo3(text_signature = "($self)")]
+    fn reset(&mut self) {
+        self.parser.reset();
+        self.result = None;
+    }
+
+    /// Bytes of input held but not yet part of a value (split tags, partial
+    /// UTF-8 and open field text), for tracking parser-held memory.
+    #[pyo3(text_signature = "($self)")]
+    fn buffered_bytes(&self) -> usize {
+        self.parser.buffered_bytes()
+    }
+
+    /// Paths of the values still open when `finish` was called, outermost
+    /// first, e.g. `["Person", "Person.hobbies", "Person.hobbies[1]"]`.
+    #[pyo3(text_signature = "($self)")]
+    fn unclosed_paths(&self) -> Vec<String> {
+        self.parser.unclosed_paths().to_vec()
+    }
+
+    /// `(path, original length)` for every string truncated to its
+    /// `MaxLength(..., on_overflow="truncate")`, in the order they closed.
+    #[pyo3(text_signature = "($self)")]
+    fn truncated_paths(&self) -> Vec<(String, usize)> {
+        self.parser.truncated_paths().to_vec()
+    }
+
+    /// `(path, offset, message)` for every error skipped under `on_error`,
+    /// in stream order.
+    #[pyo3(text_signature = "($self)")]
+    fn issues(&self) -> Vec<(String, usize, String)> {
+        self.parser
+            .issues()
+            .iter()
+            .map(|issue| (issue.path.clone(), issue.pos.offset, issue.message.clone()))
+            .collect()
+    }
+
+    /// `(path, policy)` for every empty tag replaced by an `empty_as`
+    /// policy, in the order they closed.
+    #[pyo3(text_signature = "($self)")]
+    fn empty_paths(&self) -> Vec<(String, &'static str)> {
+        self.parser
+            .empty_paths()
+            .iter()
+            .map(|(path, policy)| (path.clone(), policy.name()))
+            .collect()
+    }
+
+    /// The recorded tag events as a JSON array of `{"offset", "kind", ...}`
+    /// objects, where kind is "open", "bytes", "close" or "skipped". Only
+    /// recorded when the parser was created with `trace_tags`.
+    #[cfg(feature = "serde_json")]
+    #[pyo3(text_signature = "($self)")]
+    fn tag_trace(&self) -> String {
+        let entries: Vec<_> = self
+            .parser
+            .tag_trace()
+            .iter()
+            .map(TraceEntry::to_json)
+            .collect();
+        serde_json::Value::Array(entries).to_string()
+    }
+
+    /// The recorded tag events, one per line with offsets, indented by depth.
+    #[pyo3(text_signature = "($self)")]
+    fn format_tag_trace(&self) -> String {
+        crate::tag_finder::format_trace(self.parser.tag_trace())
+    }
+
+    /// Tags that were still open when `finish` was called, outermost first.
+    #[pyo3(text_signature = "($self)")]
+    fn unclosed_tags(&self) -> Vec<String> {
+        self.parser.unclosed_tags().to_vec()
+    }
+
     #[pyo3(text_signature = "($self)")]
     fn get_partial(&mut self, _py: Python) -> PyResult<Option<PyObject>> {
         Ok(self.result.clone())
     }
 
+    /// Values completed so far as `(tag_name, value)` pairs, in stream order.
+    /// Values are only collected when the parser was created with `multiple`.
+    #[pyo3(text_signature = "($self)")]
+    fn get_results(&self) -> Vec<(String, PyObject)> {
+        self.parser.results().to_vec()
+    }
+
+    /// Call `callback(path, value)` whenever the value at `path` (e.g.
+    /// `"Answer.steps[0]"`) is completed.
+    #[pyo3(text_signature = "($self, path, callback)")]
+    fn subscribe(&mut self, path: String, callback: PyObject) {
+        self.parser.subscribe(path, callback);
+    }
+
+    /// Text outside the payload tags since the last call. Only recorded
+    /// when the parser was created with `passthrough_text`.
+    #[pyo3(text_signature = "($self)")]
+    fn take_text(&mut self) -> String {
+        self.parser.take_text()
+    }
+
+    /// Text appended to fields since the last call, as `(path, appended)`
+    /// pairs. Only recorded when the parser was created with `string_deltas`.
+    #[pyo3(text_signature = "($self)")]
+    fn take_deltas(&mut self) -> Vec<(String, String)> {
+        self.parser.take_deltas()
+    }
+
+    /// Lifetime counters: bytes fed, tag events handled, partial snapshots
+    /// built, and the deepest nesting seen.
+    #[pyo3(text_signature = "($self)")]
+    fn stats(&self) -> HashMap<&'static str, u64> {
+        let stats = self.parser.stats();
+        HashMap::from([
+            ("bytes", stats.bytes),
+            ("events", stats.events),
+            ("snapshots", stats.snapshots),
+            ("peak_depth", stats.peak_depth as u64),
+        ])
+    }
+
+    /// Rolling parser metrics: chunks, bytes and events per second over the
+    /// recent window, average step latency, and lifetime totals.
+    #[cfg(feature = "metrics")]
+    #[pyo3(text_signature = "($self)")]
+    fn get_metrics(&self) -> HashMap<&'static str, f64> {
+        let m = self.parser.metrics();
+        HashMap::from([
+            ("chunks_per_sec", m.chunks_per_sec),
+            ("bytes_per_sec", m.bytes_per_sec),
+            ("events_per_sec", m.events_per_sec),
+            ("avg_step_latency", m.avg_step_latency.as_secs_f64()),
+            ("total_chunks", m.total_chunks as f64),
+            ("total_bytes", m.total_bytes as f64),
+            ("total_events", m.total_events as f64),
+        ])
+    }
+
+    /// Also report every step to `recorder(bytes, events, latency_secs)`,
+    /// e.g. to forward parser overhead to a metrics backend.
+    #[cfg(feature = "metrics")]
+    #[pyo3(text_signature = "($self, recorder)")]
+    fn set_metrics_recorder(&mut self, recorder: PyObject) {
+        self.parser
+            .set_metrics_recorder(Box::new(PyMetricsRecorder(recorder)));
+    }
+
+    /// Time steps with `clock()`, a callable returning monotonic seconds,
+    /// instead of the system clock, so metrics are deterministic in tests.
+    #[cfg(feature = "metrics")]
+    #[pyo3(text_signature = "($self, clock)")]
+    fn set_clock(&mut self, clock: PyObject) -> PyResult<()> {
+        self.parser.set_clock(Box::new(PyClock::new(clock)?));
+        Ok(())
+    }
+
+    /// Text of the metadata tags closed so far, keyed by tag name.
+    #[pyo3(text_signature = "($self)")]
+    fn get_metadata(&self) -> HashMap<String, String> {
+        self.parser.metadata().clone()
+    }
+
     #[pyo3(text_signature = "($self)")]
     fn validate(&mut self, _py: Python) -> PyResult<Option<PyObject>> {
         self.get_partial(_py)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static LOGGED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Keeps each test thread's log lines, so tests can run in parallel.
+    struct CaptureLogger;
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, _: &log::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record<'_>) {
+            LOGGED.with(|lines| lines.borrow_mut().push(record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    /// Log lines written while running `f` on this thread.
+    fn capture_logs(f: impl FnOnce()) -> Vec<String> {
+        static LOGGER: CaptureLogger = CaptureLogger;
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Debug);
+        LOGGED.with(|lines| lines.borrow_mut().clear());
+        f();
+        LOGGED.with(|lines| lines.take())
+    }
+
+    #[cfg(not(feature = "unsafe-debug"))]
+    #[test]
+    fn test_finish_logs_no_payload() {
+        pyo3::prepare_freethreaded_python();
+        let logs = capture_logs(|| {
+            let mut parser = TypedStreamParser::new(vec!["Answer".to_string()], vec![]);
+            let mut input = b"<Answer>secret</Answer><secret_tail".to_vec();
+            // First byte of "é", never completed
+            input.push(0xc3);
+            parser.step_bytes(&input).unwrap();
+            parser.finish().unwrap();
+        });
+        assert!(logs
+            .iter()
+            .any(|line| line.contains("dropped incomplete UTF-8")));
+        assert!(logs
+            .iter()
+            .any(|line| line.contains("dropped incomplete input")));
+        for line in &logs {
+            assert!(!line.contains("secret"), "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_drop_mid_stream_logs_buffered_bytes() {
+        pyo3::prepare_freethreaded_python();
+        let logs = capture_logs(|| {
+            let mut parser = TypedStreamParser::new(vec!["Answer".to_string()], vec![]);
+            parser.step("<Answer>hi</Ans").unwrap();
+            drop(parser);
+        });
+        assert!(
+            logs.iter()
+                .any(|line| line.contains("dropped mid-stream with 5 unconsumed buffered bytes")),
+            "{:?}",
+            logs
+        );
+
+        let logs = capture_logs(|| {
+            let mut parser = TypedStreamParser::new(vec!["Answer".to_string()], vec![]);
+            parser.step("<Answer>hi</Answer>").unwrap();
+            drop(parser);
+        });
+        assert!(!logs.iter().any(|line| line.contains("mid-stream")));
+    }
+}
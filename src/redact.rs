@@ -0,0 +1,49 @@
+//! Debug formatting for payload text.
+//!
+//! Parser state holds raw model output, so `Debug` impls print a length and
+//! hash in place of the text unless the `unsafe-debug` feature is enabled.
+
+use std::fmt;
+
+/// Formats payload bytes as `<N bytes #hash>`, or verbatim with `unsafe-debug`.
+pub(crate) struct Redacted<T: AsRef<[u8]>>(pub T);
+
+impl<T: AsRef<[u8]>> fmt::Debug for Redacted<T> {
+    #[cfg(not(feature = "unsafe-debug"))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let bytes = self.0.as_ref();
+        if bytes.is_empty() {
+            return f.write_str("<0 bytes>");
+        }
+        // Same text, same hash: lets log lines be correlated without the content
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        write!(f, "<{} bytes #{:016x}>", bytes.len(), hasher.finish())
+    }
+
+    #[cfg(feature = "unsafe-debug")]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&String::from_utf8_lossy(self.0.as_ref()), f)
+    }
+}
+
+#[cfg(all(test, not(feature = "unsafe-debug")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacted_hides_text() {
+        let shown = format!("{:?}", Redacted("secret token"));
+        assert!(shown.starts_with("<12 bytes #"), "{}", shown);
+        assert!(!shown.contains("secret"));
+        assert_eq!(
+            shown,
+            format!("{:?}", Redacted(String::from("secret token")))
+        );
+        assert_ne!(shown, format!("{:?}", Redacted("secret tokem")));
+        assert_eq!(format!("{:?}", Redacted("")), "<0 bytes>");
+    }
+}
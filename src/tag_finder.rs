@@ -1,9 +1,12 @@
 //! Incremental tag-scanner:  <Tag> … (raw bytes) … </Tag>
 
+use crate::redact::Redacted;
 use crate::xml_types::XmlError as JsonError;
 use log::debug;
+use regex::Regex;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
 
 #[derive(Debug, Clone)]
 pub struct Tag {
@@ -19,39 +22,355 @@ pub enum TagEvent {
     Close(String, usize), // </Tag>
 }
 
-#[derive(Debug)]
+type TextCallback = Box<dyn FnMut(usize, &str) + Send>;
+
 pub struct TagFinder {
     buf: String,                                // carries over up to a whole unfinished tag
-    depth: usize,                               // current tag depth
-    inside: bool,                               // true ⇢ we're between <Tag> … </Tag>
+    open_tags: Vec<String>, // tags open inside the current wanted tag, outermost first (empty ⇢ not inside one)
     wanted: std::collections::HashSet<String>, // tags we specifically want to process (empty = all)
+    wanted_patterns: Vec<Regex>, // wanted globs such as `tool_*`, and wanted regexes
     ignored: std::collections::HashSet<String>, // tags to ignore content within
-    inside_ignored: bool,                      // true if we're currently inside an ignored tag
-    ignored_depth: usize,                      // depth of nested ignored tags
+    inside_ignored: bool,   // true if we're currently inside an ignored tag
+    ignored_depth: usize,   // depth of nested ignored tags
+    passthrough: Option<String>, // text outside wanted tags, when capturing it
+    on_text: Option<TextCallback>, // called with text outside wanted tags
+    buf_offset: usize,      // stream offset of the first byte of `buf`
+    trace: Option<Vec<TraceEntry>>, // every event and skipped tag, when tracing
+    queued: VecDeque<(usize, TagEvent)>, // events from `feed` not yet pulled
+    #[cfg(feature = "tracing")]
+    tag_spans: Vec<(tracing::Span, usize)>, // one span per emitted open tag, with its payload bytes so far
+}
+
+/// Decode the next chunk of a UTF-8 byte stream. A multi-byte character
+/// split across chunks is held in `carry` until the rest of it arrives;
+/// invalid bytes are replaced with U+FFFD.
+pub(crate) fn decode_utf8_chunk(carry: &mut Vec<u8>, chunk: &[u8]) -> String {
+    let mut bytes = std::mem::take(carry);
+    bytes.extend_from_slice(chunk);
+
+    let mut text = String::with_capacity(bytes.len());
+    let mut rest = bytes.as_slice();
+    loop {
+        match std::str::from_utf8(rest) {
+            Ok(valid) => {
+                text.push_str(valid);
+                break;
+            }
+            Err(err) => {
+                let (valid, after) = rest.split_at(err.valid_up_to());
+                // from_utf8 has just validated this prefix
+                text.push_str(std::str::from_utf8(valid).unwrap());
+                match err.error_len() {
+                    Some(len) => {
+                        text.push(char::REPLACEMENT_CHARACTER);
+                        rest = &after[len..];
+                    }
+                    None => {
+                        // Truncated sequence at the end; wait for more bytes
+                        *carry = after.to_vec();
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    text
+}
+
+/// Why a tag produced no event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// An ignored tag such as `<think>`
+    Ignored,
+    /// Any tag inside an ignored tag
+    InsideIgnored,
+    /// Outside every wanted tag and not wanted itself
+    NotWanted,
+    /// A close tag with no matching open tag
+    Unmatched,
+}
+
+impl SkipReason {
+    fn name(self) -> &'static str {
+        match self {
+            SkipReason::Ignored => "ignored",
+            SkipReason::InsideIgnored => "inside_ignored",
+            SkipReason::NotWanted => "not_wanted",
+            SkipReason::Unmatched => "unmatched_close",
+        }
+    }
+}
+
+/// What happened at one point of a traced stream.
+#[derive(Debug, Clone)]
+pub enum TraceKind {
+    Event(TagEvent),
+    Skipped { tag: String, reason: SkipReason },
+}
+
+/// One traced event, at the byte offset in the whole stream where its tag
+/// (or text) starts.
+#[derive(Debug, Clone)]
+pub struct TraceEntry {
+    pub offset: usize,
+    pub kind: TraceKind,
+}
+
+#[cfg(feature = "serde_json")]
+impl TraceEntry {
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::json;
+        match &self.kind {
+            TraceKind::Event(TagEvent::Open(tag)) => json!({
+                "offset": self.offset,
+                "kind": "open",
+                "name": tag.name,
+                "depth": tag.depth,
+                "attributes": tag.attributes,
+            }),
+            TraceKind::Event(TagEvent::Bytes(text)) => json!({
+                "offset": self.offset,
+                "kind": "bytes",
+                "text": text,
+            }),
+            TraceKind::Event(TagEvent::Close(name, depth)) => json!({
+                "offset": self.offset,
+                "kind": "close",
+                "name": name,
+                "depth": depth,
+            }),
+            TraceKind::Skipped { tag, reason } => json!({
+                "offset": self.offset,
+                "kind": "skipped",
+                "name": tag,
+                "reason": reason.name(),
+            }),
+        }
+    }
+}
+
+/// Same shape as `to_json`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for TraceEntry {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+/// Render a trace one entry per line, indented by depth:
+///
+/// ```text
+///      0  skip <think> (ignored)
+///     30  +Answer@1
+///     38    "hello"
+///     43  -Answer@1
+/// ```
+pub fn format_trace(entries: &[TraceEntry]) -> String {
+    let mut out = String::new();
+    let mut depth = 0;
+    for entry in entries {
+        let line = match &entry.kind {
+            TraceKind::Event(TagEvent::Open(tag)) => {
+                depth = tag.depth;
+                let mut attributes: Vec<_> = tag.attributes.iter().collect();
+                attributes.sort();
+                let attributes: String = attributes
+                    .into_iter()
+                    .map(|(k, v)| format!(" {}={:?}", k, v))
+                    .collect();
+                format!(
+                    "{}+{}@{}{}",
+                    "  ".repeat(tag.depth - 1),
+                    tag.name,
+                    tag.depth,
+                    attributes
+                )
+            }
+            TraceKind::Event(TagEvent::Bytes(text)) => {
+                format!("{}{:?}", "  ".repeat(depth), text)
+            }
+            TraceKind::Event(TagEvent::Close(name, close_depth)) => {
+                depth = close_depth - 1;
+                format!("{}-{}@{}", "  ".repeat(depth), name, close_depth)
+            }
+            TraceKind::Skipped { tag, reason } => {
+                format!("{}skip <{}> ({})", "  ".repeat(depth), tag, reason.name())
+            }
+        };
+        out.push_str(&format!("{:>6}  {}\n", entry.offset, line));
+    }
+    out
+}
+
+/// Iterator returned by `TagFinder::events`.
+#[derive(Debug)]
+pub struct Events<'a> {
+    finder: &'a mut TagFinder,
+}
+
+impl Iterator for Events<'_> {
+    type Item = (usize, TagEvent);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.finder.next_event()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.finder.queued.len();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Events<'_> {}
+
+impl fmt::Debug for TagFinder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TagFinder")
+            .field("buf", &Redacted(&self.buf))
+            .field("open_tags", &self.open_tags)
+            .field("wanted", &self.wanted)
+            .field("wanted_patterns", &self.wanted_patterns)
+            .field("ignored", &self.ignored)
+            .field("inside_ignored", &self.inside_ignored)
+            .field("ignored_depth", &self.ignored_depth)
+            .field("buf_offset", &self.buf_offset)
+            .field("trace", &self.trace.as_ref().map(Vec::len))
+            .field("queued", &self.queued.len())
+            .field("passthrough", &self.passthrough.as_ref().map(Redacted))
+            .field("on_text", &self.on_text.is_some())
+            .finish()
+    }
+}
+
+fn is_glob(tag: &str) -> bool {
+    tag.contains(['*', '?'])
+}
+
+/// `*` matches any run of characters and `?` a single one; everything else
+/// is literal. The whole tag name must match, ignoring case.
+fn glob_to_regex(glob: &str) -> Regex {
+    let mut pattern = String::from("(?i)^");
+    let mut literal = String::new();
+    for c in glob.chars() {
+        if c == '*' || c == '?' {
+            pattern.push_str(&regex::escape(&literal));
+            literal.clear();
+            pattern.push_str(if c == '*' { ".*" } else { "." });
+        } else {
+            literal.push(c);
+        }
+    }
+    pattern.push_str(&regex::escape(&literal));
+    pattern.push('$');
+    Regex::new(&pattern).expect("escaped glob is a valid regex")
 }
 
 impl Default for TagFinder {
     fn default() -> Self {
         Self {
             buf: String::new(),
-            depth: 0,
-            inside: false,
+            open_tags: Vec::new(),
             wanted: std::collections::HashSet::new(),
+            wanted_patterns: Vec::new(),
             ignored: std::collections::HashSet::new(),
             inside_ignored: false,
             ignored_depth: 0,
+            passthrough: None,
+            on_text: None,
+            buf_offset: 0,
+            trace: None,
+            queued: VecDeque::new(),
+            #[cfg(feature = "tracing")]
+            tag_spans: Vec::new(),
         }
     }
 }
 
+/// Options for a `TagFinder`, set by name:
+///
+/// ```
+/// use gasp::prelude::*;
+///
+/// let mut finder = TagFinder::builder()
+///     .wanted(["Answer"])
+///     .ignored(["think"])
+///     .trace(true)
+///     .build();
+/// finder.feed("<think>hm</think><Answer>42</Answer>").unwrap();
+/// assert_eq!(finder.events().count(), 3);
+/// assert_eq!(finder.trace().len(), 5);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TagFinderBuilder {
+    wanted: Vec<String>,
+    wanted_regexes: Vec<Regex>,
+    ignored: Vec<String>,
+    passthrough: bool,
+    trace: bool,
+}
+
+impl TagFinderBuilder {
+    /// Tags to report; see `TagFinder::new_with_filter`. Adds to earlier calls.
+    pub fn wanted<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.wanted.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// Also report tags whose name, as written, matches `pattern` anywhere;
+    /// anchor it with `^...$` to match whole names. Adds to earlier calls.
+    pub fn wanted_regex(mut self, pattern: Regex) -> Self {
+        self.wanted_regexes.push(pattern);
+        self
+    }
+
+    /// Tags to skip along with their content. Adds to earlier calls.
+    pub fn ignored<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.ignored.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// See `TagFinder::set_passthrough`.
+    pub fn passthrough(mut self, enabled: bool) -> Self {
+        self.passthrough = enabled;
+        self
+    }
+
+    /// See `TagFinder::set_trace`.
+    pub fn trace(mut self, enabled: bool) -> Self {
+        self.trace = enabled;
+        self
+    }
+
+    pub fn build(self) -> TagFinder {
+        let mut finder = TagFinder::new_with_filter(self.wanted, self.ignored);
+        finder.wanted_patterns.extend(self.wanted_regexes);
+        finder.set_passthrough(self.passthrough);
+        finder.set_trace(self.trace);
+        finder
+    }
+}
+
 impl TagFinder {
     pub fn new() -> Self {
         Self::default()
     }
 
+    pub fn builder() -> TagFinderBuilder {
+        TagFinderBuilder::default()
+    }
+
     /// Create a new TagFinder with specific wanted and ignored tags
     ///
     /// * `wanted` - Tags to specifically process. If empty, all non-ignored tags are processed.
+    ///   Entries containing `*` or `?` are globs, e.g. `tool_*` matches `<tool_search>`.
     /// * `ignored` - Tags to completely ignore. These tags and their content will be skipped.
     pub fn new_with_filter(wanted: Vec<String>, ignored: Vec<String>) -> Self {
         debug!(
@@ -59,8 +378,12 @@ impl TagFinder {
             wanted, ignored
         );
         // Store lowercase versions for case-insensitive matching
-        let wanted_set: std::collections::HashSet<String> =
-            wanted.into_iter().map(|s| s.to_lowercase()).collect();
+        let (globs, exact): (Vec<String>, Vec<String>) = wanted
+            .into_iter()
+            .map(|s| s.to_lowercase())
+            .partition(|s| is_glob(s));
+        let wanted_set: std::collections::HashSet<String> = exact.into_iter().collect();
+        let wanted_patterns: Vec<Regex> = globs.iter().map(|g| glob_to_regex(g)).collect();
         let ignored_set: std::collections::HashSet<String> =
             ignored.into_iter().map(|s| s.to_lowercase()).collect();
         debug!(
@@ -69,74 +392,302 @@ impl TagFinder {
         );
         Self {
             buf: String::new(),
-            depth: 0,
-            inside: false,
+            open_tags: Vec::new(),
             wanted: wanted_set,
+            wanted_patterns,
             ignored: ignored_set,
             inside_ignored: false,
             ignored_depth: 0,
+            passthrough: None,
+            on_text: None,
+            buf_offset: 0,
+            trace: None,
+            queued: VecDeque::new(),
+            #[cfg(feature = "tracing")]
+            tag_spans: Vec::new(),
+        }
+    }
+    /// Add another wanted tag after construction. This is a no-op when the
+    /// wanted list is empty, since every non-ignored tag is already wanted.
+    pub fn add_wanted(&mut self, tag: &str) {
+        if self.wanted.is_empty() && self.wanted_patterns.is_empty() {
+            return;
+        }
+        let tag = tag.to_lowercase();
+        if is_glob(&tag) {
+            self.wanted_patterns.push(glob_to_regex(&tag));
+        } else {
+            self.wanted.insert(tag);
+        }
+    }
+
+    /// Also keep the prose outside wanted tags (e.g. reasoning before an
+    /// `<Action>`) instead of discarding it. Ignored tags stay dropped.
+    pub fn set_passthrough(&mut self, enabled: bool) {
+        self.passthrough = enabled.then(String::new);
+    }
+
+    /// Take the text outside wanted tags seen since the last call.
+    pub fn take_passthrough(&mut self) -> String {
+        self.passthrough
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Call `callback` with each piece of text outside wanted tags, and its
+    /// stream offset, as soon as it is scanned. Works with or without
+    /// `set_passthrough`; ignored tags stay dropped.
+    ///
+    /// ```
+    /// use gasp::prelude::*;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let prose = Arc::new(Mutex::new(String::new()));
+    /// let sink = Arc::clone(&prose);
+    /// let mut finder = TagFinder::new_with_filter(vec!["Action".into()], vec![]);
+    /// finder.on_text(move |_offset, text| sink.lock().unwrap().push_str(text));
+    /// finder.push("Let me look. <Action>go</Action>", |_, _| Ok(())).unwrap();
+    /// assert_eq!(*prose.lock().unwrap(), "Let me look. ");
+    /// ```
+    pub fn on_text(&mut self, callback: impl FnMut(usize, &str) + Send + 'static) {
+        self.on_text = Some(Box::new(callback));
+    }
+
+    fn captures_text(&self) -> bool {
+        self.passthrough.is_some() || self.on_text.is_some()
+    }
+
+    /// Pass text outside wanted tags, starting at stream offset `offset`, to
+    /// the passthrough buffer and the `on_text` callback.
+    fn pass_through(&mut self, offset: usize, text: &str) {
+        if self.inside_ignored || text.is_empty() {
+            return;
+        }
+        if let Some(passthrough) = self.passthrough.as_mut() {
+            passthrough.push_str(text);
+        }
+        if let Some(callback) = self.on_text.as_mut() {
+            callback(offset, text);
+        }
+    }
+
+    /// Record every event and every skipped tag, with stream offsets, so a
+    /// tag that was not captured can be explained after the fact.
+    pub fn set_trace(&mut self, enabled: bool) {
+        self.trace = enabled.then(Vec::new);
+    }
+
+    /// The trace recorded so far; empty unless tracing is on.
+    pub fn trace(&self) -> &[TraceEntry] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    fn skip(&mut self, offset: usize, tag: &str, reason: SkipReason) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            offset = self.buf_offset + offset,
+            tag,
+            ?reason,
+            "skipped tag"
+        );
+        #[cfg(not(feature = "tracing"))]
+        debug!("[TagFinder] Skipped <{}> ({:?})", tag, reason);
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(TraceEntry {
+                offset: self.buf_offset + offset,
+                kind: TraceKind::Skipped {
+                    tag: tag.to_string(),
+                    reason,
+                },
+            });
+        }
+    }
+
+    /// Emit `event` with its stream offset, given as `offset` into `buf`.
+    fn emit_at(
+        &mut self,
+        offset: usize,
+        event: TagEvent,
+        emit: &mut impl FnMut(usize, TagEvent) -> Result<(), JsonError>,
+    ) -> Result<(), JsonError> {
+        let offset = self.buf_offset + offset;
+        #[cfg(feature = "tracing")]
+        self.trace_event(offset, &event);
+        #[cfg(not(feature = "tracing"))]
+        match &event {
+            TagEvent::Open(tag) => debug!("[TagFinder] Open <{}> at {}", tag.name, offset),
+            TagEvent::Bytes(text) => debug!("[TagFinder] {} bytes at {}", text.len(), offset),
+            TagEvent::Close(name, _) => debug!("[TagFinder] Close </{}> at {}", name, offset),
+        }
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(TraceEntry {
+                offset,
+                kind: TraceKind::Event(event.clone()),
+            });
+        }
+        emit(offset, event)
+    }
+
+    /// Open a span per tag, nested under its parent tag's span, and close it
+    /// with the number of payload bytes it held.
+    #[cfg(feature = "tracing")]
+    fn trace_event(&mut self, offset: usize, event: &TagEvent) {
+        match event {
+            TagEvent::Open(tag) => {
+                let parent = self.tag_spans.last().and_then(|(span, _)| span.id());
+                let span = tracing::debug_span!(
+                    parent: parent,
+                    "tag",
+                    name = %tag.name,
+                    depth = tag.depth,
+                    offset,
+                    bytes = tracing::field::Empty,
+                );
+                self.tag_spans.push((span, 0));
+            }
+            TagEvent::Bytes(text) => {
+                if let Some((_, bytes)) = self.tag_spans.last_mut() {
+                    *bytes += text.len();
+                }
+            }
+            TagEvent::Close(_, depth) => {
+                let keep = depth.saturating_sub(1).min(self.tag_spans.len());
+                for (span, bytes) in self.tag_spans.drain(keep..).rev() {
+                    span.record("bytes", bytes);
+                }
+            }
+        }
+    }
+
+    /// Drop the first `len` bytes of `buf`, keeping stream offsets right.
+    fn consume(&mut self, len: usize) {
+        self.buf.drain(..len);
+        self.buf_offset += len;
+    }
+
+    /// Bytes held back waiting for the rest of a split tag.
+    pub fn buffered_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Signal the end of input. Any partial tag still buffered is dropped and
+    /// the scanner returns to its initial state; returns the dropped text.
+    pub fn finish(&mut self) -> String {
+        debug!(
+            "[TagFinder::finish] Dropping {} buffered bytes",
+            self.buf.len()
+        );
+        self.open_tags.clear();
+        #[cfg(feature = "tracing")]
+        self.tag_spans.clear();
+        self.inside_ignored = false;
+        self.ignored_depth = 0;
+        self.buf_offset += self.buf.len();
+        std::mem::take(&mut self.buf)
+    }
+
+    /// Pull-style alternative to `push`: scan `chunk` and queue its events,
+    /// with their stream offsets, for `events` or `next_event`.
+    pub fn feed(&mut self, chunk: &str) -> Result<(), JsonError> {
+        let mut queued = std::mem::take(&mut self.queued);
+        let result = self.push(chunk, |offset, event| {
+            queued.push_back((offset, event));
+            Ok(())
+        });
+        self.queued = queued;
+        result
+    }
+
+    /// Take the oldest queued event, if any.
+    pub fn next_event(&mut self) -> Option<(usize, TagEvent)> {
+        self.queued.pop_front()
+    }
+
+    /// Iterate over the queued events one at a time. Events not pulled
+    /// before the iterator is dropped stay queued for later.
+    pub fn events(&mut self) -> Events<'_> {
+        Events { finder: self }
+    }
+
+    /// Forget the current stream but keep the filters and modes, so the
+    /// finder (and its buffers' capacity) can be reused for the next one.
+    pub fn reset(&mut self) {
+        self.buf.clear();
+        self.open_tags.clear();
+        self.inside_ignored = false;
+        self.ignored_depth = 0;
+        if let Some(passthrough) = self.passthrough.as_mut() {
+            passthrough.clear();
+        }
+        self.buf_offset = 0;
+        if let Some(trace) = self.trace.as_mut() {
+            trace.clear();
         }
+        self.queued.clear();
+        #[cfg(feature = "tracing")]
+        self.tag_spans.clear();
     }
+
+    fn inside(&self) -> bool {
+        !self.open_tags.is_empty()
+    }
+
     /// Feed the next text chunk, emitting TagEvents.
     /// `emit` will be called with:
     ///   • TagEvent::Open  { name }
     ///   • TagEvent::Bytes(payload)
     ///   • TagEvent::Close { name }
+    /// along with the byte offset in the whole stream where the event's tag
+    /// (or text) starts.
     pub fn push(
         &mut self,
         chunk: &str,
-        mut emit: impl FnMut(TagEvent) -> Result<(), JsonError>,
+        mut emit: impl FnMut(usize, TagEvent) -> Result<(), JsonError>,
     ) -> Result<(), JsonError> {
-        debug!("[TagFinder::push] Received chunk: '{}'", chunk);
+        #[cfg(feature = "tracing")]
+        let _chunk = tracing::debug_span!(
+            "tag_finder.push",
+            bytes = chunk.len(),
+            offset = self.buf_offset + self.buf.len(),
+            buffered = self.buf.len(),
+        )
+        .entered();
         self.buf.push_str(chunk);
-        debug!("[TagFinder::push] Current buffer: '{}'", self.buf);
-        debug!("[TagFinder::push] Current state: depth={}, inside={}, inside_ignored={}, ignored_depth={}, wanted={:?}, ignored={:?}", self.depth, self.inside, self.inside_ignored, self.ignored_depth, self.wanted, self.ignored);
-
-        loop {
-            debug!("[TagFinder::push] Loop start. Buffer: '{}'", self.buf);
-            /*──────── look for the next '<' ───────────────────────────*/
-            let lt = match self.buf.find('<') {
-                Some(i) => i,
-                None => break,
-            };
+
+        // Start of the unprocessed part of `buf`. Handled input is drained
+        // once per push rather than once per tag, which would make a chunk
+        // holding many tags quadratic in its length.
+        let mut pos = 0;
+
+        /*──────── look for the next '<' ───────────────────────────*/
+        while let Some(i) = self.buf[pos..].find('<') {
+            let lt = pos + i;
 
             /*──────── everything *before* it is payload ──────────────*/
-            if lt > 0 {
-                let leading_text = self.buf[..lt].to_owned();
-                debug!(
-                    "[TagFinder::push] Found '<' at index {}. Leading text: '{}'",
-                    lt, leading_text
-                );
-                if self.inside && !self.inside_ignored && !leading_text.is_empty() {
-                    debug!(
-                        "[TagFinder::push] Emitting Bytes for leading_text: '{}'",
-                        leading_text
-                    );
-                    emit(TagEvent::Bytes(leading_text))?;
+            if lt > pos {
+                let leading_text = self.buf[pos..lt].to_owned();
+                if self.inside() && !self.inside_ignored && !leading_text.is_empty() {
+                    self.emit_at(pos, TagEvent::Bytes(leading_text), &mut emit)?;
                 } else {
-                    debug!("[TagFinder::push] Not emitting leading_text (inside: {}, inside_ignored: {}, empty: {})", self.inside, self.inside_ignored, leading_text.is_empty());
+                    if !self.inside() {
+                        self.pass_through(self.buf_offset + pos, &leading_text);
+                    }
                 }
-            } else {
-                debug!("[TagFinder::push] Found '<' at index 0. No leading text.");
             }
 
             // Handle CDATA sections
             if self.buf[lt..].starts_with("<![CDATA[") {
                 if let Some(cdata_end) = self.buf[lt..].find("]]>") {
                     let cdata_content = self.buf[lt + 9..lt + cdata_end].to_string();
-                    if self.inside && !self.inside_ignored && !cdata_content.is_empty() {
-                        debug!(
-                            "[TagFinder::push] Emitting Bytes for CDATA: '{}'",
-                            cdata_content
-                        );
-                        emit(TagEvent::Bytes(cdata_content))?;
+                    if self.inside() && !self.inside_ignored && !cdata_content.is_empty() {
+                        self.emit_at(lt + 9, TagEvent::Bytes(cdata_content), &mut emit)?;
                     }
-                    self.buf.drain(..lt + cdata_end + 3);
+                    pos = lt + cdata_end + 3;
                     continue; // Continue to next iteration of the loop
                 } else {
                     // Incomplete CDATA section, wait for more data
-                    debug!("[TagFinder::push] Incomplete CDATA section. Draining buf up to lt: {}. Remaining buf: '{}'", lt, &self.buf[lt..]);
-                    self.buf.drain(..lt);
+                    self.consume(lt);
                     return Ok(());
                 }
             }
@@ -146,20 +697,21 @@ impl TagFinder {
                 Some(off) => lt + off,
                 None => {
                     // tag split across chunks → keep tail for next push()
-                    debug!("[TagFinder::push] Tag split across chunks. Draining buf up to lt: {}. Remaining buf: '{}'", lt, &self.buf[lt..]);
-                    self.buf.drain(..lt); // drop handled bytes before the incomplete tag
+                    self.consume(lt); // drop handled bytes before the incomplete tag
                     return Ok(());
                 }
             };
-            debug!(
-                "[TagFinder::push] Found matching '>' at index {}. Tag content: '{}'",
-                gt,
-                &self.buf[lt..=gt]
-            );
 
             /*──────── analyse the tag ────────────────────────────────*/
             let tag_body = &self.buf[lt + 1..gt]; // without '<' / '>'
             let is_close = tag_body.starts_with('/');
+            // <Tag/> opens and closes in one go
+            let is_self_closing = !is_close && tag_body.ends_with('/');
+            let tag_body = if is_self_closing {
+                &tag_body[..tag_body.len() - 1]
+            } else {
+                tag_body
+            };
             let name_part = if is_close { &tag_body[1..] } else { tag_body };
 
             // Find the first whitespace to separate tag name from attributes
@@ -235,151 +787,111 @@ impl TagFinder {
                 }
             }
 
-            debug!(
-                "[TagFinder::push] Tag analysis: body='{}', is_close={}, name='{}', name_lower='{}', attributes='{:?}'",
-                tag_body, is_close, name, name_lower, attributes
-            );
-
             // Check if this tag is ignored (use lowercase for comparison)
             let is_ignored = self.ignored.contains(&name_lower);
-            debug!(
-                "[TagFinder::push] Tag '{}' (lower: '{}') is_ignored: {} (self.ignored (lowercase): {:?})",
-                name, name_lower, is_ignored, self.ignored
-            );
 
             // Check if this tag is wanted (use lowercase for comparison)
-            let is_wanted = if self.wanted.is_empty() {
+            let is_wanted = if self.wanted.is_empty() && self.wanted_patterns.is_empty() {
                 !is_ignored // If not specifically ignored, and wanted list is empty, it's wanted.
             } else {
                 self.wanted.contains(&name_lower)
+                    || self.wanted_patterns.iter().any(|p| p.is_match(&name))
             };
-            debug!(
-                "[TagFinder::push] Tag '{}' (lower: '{}') is_wanted: {} (self.wanted (lowercase): {:?})",
-                name, name_lower, is_wanted, self.wanted
-            );
 
             // Don't skip nested tags - we need to emit them as proper tag events
             // so the parser can handle object fields properly
 
             if !is_close {
                 /* <Tag> : opening tag */
-                self.depth += 1;
-                debug!(
-                    "[TagFinder::push] Processing Open Tag: '{}' at depth {}",
-                    name, self.depth
-                );
-                if is_ignored {
-                    self.inside_ignored = true;
-                    self.ignored_depth += 1;
-                    debug!("[TagFinder::push] Opened ignored tag '{}'. inside_ignored={}, ignored_depth={}", name, self.inside_ignored, self.ignored_depth);
-                } else if self.inside && !self.inside_ignored {
-                    // If we're inside a wanted tag, emit ALL nested tags (regardless of whether they're in the wanted list)
-                    debug!(
-                        "[TagFinder::push] Emitting Open for nested tag inside wanted tag: '{}'",
-                        name
-                    );
-                    emit(TagEvent::Open(Tag {
-                        name: name.clone(),
-                        attributes,
-                        depth: self.depth,
-                    }))?;
-                } else if is_wanted && !self.inside_ignored {
-                    debug!("[TagFinder::push] Emitting Open for wanted tag: '{}'", name);
-                    emit(TagEvent::Open(Tag {
+                let depth = self.open_tags.len() + 1;
+                if self.inside_ignored {
+                    if is_ignored {
+                        self.ignored_depth += 1;
+                    }
+                    self.skip(lt, &name, SkipReason::InsideIgnored);
+                } else if is_ignored {
+                    if !is_self_closing {
+                        self.inside_ignored = true;
+                        self.ignored_depth += 1;
+                    }
+                    self.skip(lt, &name, SkipReason::Ignored);
+                } else if self.inside() || is_wanted {
+                    // Once inside a wanted tag, ALL nested tags are emitted (regardless of
+                    // whether they're in the wanted list) so the parser can build fields.
+                    let open = TagEvent::Open(Tag {
                         name: name.clone(),
                         attributes,
-                        depth: self.depth,
-                    }))?;
-                    if !self.inside {
-                        self.inside = true;
-                        debug!(
-                            "[TagFinder::push] Set self.inside = true for tag '{}'",
-                            name
-                        );
+                        depth,
+                    });
+                    self.emit_at(lt, open, &mut emit)?;
+                    if is_self_closing {
+                        self.emit_at(lt, TagEvent::Close(name.clone(), depth), &mut emit)?;
                     } else {
-                        debug!(
-                            "[TagFinder::push] Already self.inside=true for tag '{}'",
-                            name
-                        );
+                        self.open_tags.push(name.clone());
                     }
                 } else {
-                    debug!("[TagFinder::push] Open Tag '{}' is not wanted or currently inside ignored. is_wanted={}, inside_ignored={}", name, is_wanted, self.inside_ignored);
+                    self.skip(lt, &name, SkipReason::NotWanted);
                 }
             } else {
                 /* </Tag> : closing tag */
-                debug!(
-                    "[TagFinder::push] Processing Close Tag: '{}' at depth {}",
-                    name, self.depth
-                );
-                if is_ignored && self.inside_ignored {
-                    self.ignored_depth -= 1;
-                    if self.ignored_depth == 0 {
-                        self.inside_ignored = false;
-                    }
-                    debug!("[TagFinder::push] Closed ignored tag '{}'. inside_ignored={}, ignored_depth={}", name, self.inside_ignored, self.ignored_depth);
-                } else if self.inside && !self.inside_ignored {
-                    // If we're inside a wanted tag, emit ALL nested closing tags
-                    debug!(
-                        "[TagFinder::push] Emitting Close for nested tag inside wanted tag: '{}'",
-                        name
-                    );
-                    emit(TagEvent::Close(name.clone(), self.depth))?;
-                    // Only set inside=false if this is closing the main wanted tag
-                    if is_wanted && self.depth == 1 {
-                        self.inside = false;
-                        debug!(
-                            "[TagFinder::push] Set self.inside = false for wanted tag '{}'",
-                            name
-                        );
+                if self.inside_ignored {
+                    if is_ignored {
+                        self.ignored_depth -= 1;
+                        if self.ignored_depth == 0 {
+                            self.inside_ignored = false;
+                        }
                     }
-                } else if is_wanted && !self.inside_ignored {
-                    debug!(
-                        "[TagFinder::push] Emitting Close for wanted tag: '{}'",
-                        name
-                    );
-                    emit(TagEvent::Close(name.clone(), self.depth))?;
-                    if self.depth == 1 {
-                        self.inside = false; // Assuming this closes the primary wanted tag
-                        debug!(
-                            "[TagFinder::push] Set self.inside = false for tag '{}'",
-                            name
-                        );
+                    let reason = if is_ignored && !self.inside_ignored {
+                        SkipReason::Ignored
+                    } else {
+                        SkipReason::InsideIgnored
+                    };
+                    self.skip(lt, &name, reason);
+                } else if let Some(idx) = self
+                    .open_tags
+                    .iter()
+                    .rposition(|open| open.to_lowercase() == name_lower)
+                {
+                    // Tags left open inside this one are closed first, innermost
+                    // first, so the events always nest properly.
+                    while self.open_tags.len() > idx + 1 {
+                        let depth = self.open_tags.len();
+                        let unclosed = self.open_tags.pop().unwrap();
+                        self.emit_at(lt, TagEvent::Close(unclosed, depth), &mut emit)?;
                     }
+                    self.open_tags.pop();
+                    self.emit_at(lt, TagEvent::Close(name.clone(), idx + 1), &mut emit)?;
                 } else {
-                    debug!("[TagFinder::push] Close Tag '{}' is not wanted or currently inside ignored. is_wanted={}, inside_ignored={}", name, is_wanted, self.inside_ignored);
-                }
-                if self.depth > 0 {
-                    self.depth -= 1;
+                    self.skip(lt, &name, SkipReason::Unmatched);
                 }
             }
 
             /*──────── consume the tag itself ─────────────────────────*/
-            self.buf.drain(..gt + 1);
-            debug!(
-                "[TagFinder::push] Drained processed tag. Remaining buf: '{}'",
-                self.buf
-            );
+            pos = gt + 1;
         }
-        debug!("[TagFinder::push] Loop end. Final buffer: '{}'", self.buf);
+        self.consume(pos);
 
         /*──────── no '<' left in buffer – handle tail ───────────────*/
-        if self.inside && !self.inside_ignored && !self.buf.is_empty() {
+        if self.inside() && !self.inside_ignored && !self.buf.is_empty() {
             let tail_payload = std::mem::take(&mut self.buf);
-            debug!(
-                "[TagFinder::push] Emitting Bytes for tail payload: '{}'",
-                tail_payload
-            );
-            emit(TagEvent::Bytes(tail_payload))?;
+            let len = tail_payload.len();
+            self.emit_at(0, TagEvent::Bytes(tail_payload), &mut emit)?;
+            self.buf_offset += len;
         } else {
-            debug!(
-                "[TagFinder::push] Tail handling: inside={}, inside_ignored={}, buf_empty={}",
-                self.inside,
-                self.inside_ignored,
-                self.buf.is_empty()
-            );
-            // keep only a tiny tail (≤200 chars) to recognise a split tag
-            let keep = self.buf.len().min(200);
-            let tail = self.buf.split_off(self.buf.len() - keep);
+            if self.captures_text() && !self.inside_ignored {
+                // Nothing here can start a tag, so it is all prose
+                let text = std::mem::take(&mut self.buf);
+                self.pass_through(self.buf_offset, &text);
+                self.buf_offset += text.len();
+            }
+            // keep only a tiny tail (≤200 bytes) to recognise a split tag,
+            // starting on a character boundary
+            let mut cut = self.buf.len() - self.buf.len().min(200);
+            while !self.buf.is_char_boundary(cut) {
+                cut += 1;
+            }
+            let tail = self.buf.split_off(cut);
+            self.buf_offset += self.buf.len();
             self.buf = tail;
         }
         Ok(())
@@ -389,6 +901,7 @@ impl TagFinder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_split_tag_handling() {
@@ -400,7 +913,7 @@ mod tests {
 
         // First chunk contains part of opening tag
         finder
-            .push("<Report", |event| {
+            .push("<Report", |_, event| {
                 events.push(event);
                 Ok(())
             })
@@ -411,7 +924,7 @@ mod tests {
 
         // Second chunk completes the opening tag
         finder
-            .push("Sub>{", |event| {
+            .push("Sub>{", |_, event| {
                 events.push(event);
                 Ok(())
             })
@@ -440,7 +953,7 @@ mod tests {
 
         // Third chunk has content and closing tag
         finder
-            .push(" more content</ReportSub>", |event| {
+            .push(" more content</ReportSub>", |_, event| {
                 events.push(event);
                 Ok(())
             })
@@ -473,7 +986,7 @@ mod tests {
 
         // First chunk just has opening bracket
         finder
-            .push("<", |event| {
+            .push("<", |_, event| {
                 events.push(event);
                 Ok(())
             })
@@ -483,7 +996,7 @@ mod tests {
 
         // Second chunk has tag name
         finder
-            .push("ReportSub", |event| {
+            .push("ReportSub", |_, event| {
                 events.push(event);
                 Ok(())
             })
@@ -493,7 +1006,7 @@ mod tests {
 
         // Third chunk completes the opening tag
         finder
-            .push(">content", |event| {
+            .push(">content", |_, event| {
                 events.push(event);
                 Ok(())
             })
@@ -518,14 +1031,14 @@ mod tests {
         events.clear();
 
         finder
-            .push("</Report", |event| {
+            .push("</Report", |_, event| {
                 events.push(event);
                 Ok(())
             })
             .unwrap();
 
         finder
-            .push("Sub>", |event| {
+            .push("Sub>", |_, event| {
                 events.push(event);
                 Ok(())
             })
@@ -574,7 +1087,7 @@ mod tests {
 
         // Process the input
         finder
-            .push(input, |event| {
+            .push(input, |_, event| {
                 println!("Event: {:?}", event);
                 events.push(event);
                 Ok(())
@@ -657,7 +1170,7 @@ mod tests {
 
         // Process the input
         finder
-            .push(input, |event| {
+            .push(input, |_, event| {
                 println!("Event: {:?}", event);
                 events.push(event);
                 Ok(())
@@ -730,7 +1243,7 @@ mod tests {
 
         // Process the input
         finder
-            .push(input, |event| {
+            .push(input, |_, event| {
                 println!("Event: {:?}", event);
                 events.push(event);
                 Ok(())
@@ -812,7 +1325,7 @@ mod tests {
         let input = r#"<root>some text <![CDATA[with <tags> & entities]]> more text</root>"#;
 
         finder
-            .push(input, |event| {
+            .push(input, |_, event| {
                 println!("Event: {:?}", event);
                 events.push(event);
                 Ok(())
@@ -855,7 +1368,7 @@ mod tests {
 
         for chunk in chunks {
             finder
-                .push(chunk, |event| {
+                .push(chunk, |_, event| {
                     events.push(event);
                     Ok(())
                 })
@@ -872,4 +1385,655 @@ mod tests {
         let full_content = content_chunks.join("");
         assert_eq!(full_content.trim(), "part1 part2 text");
     }
+
+    #[test]
+    fn test_tag_attributes() {
+        let mut finder = TagFinder::new();
+        let mut events = Vec::new();
+        for chunk in [
+            r#"<Action type="web search" id='3' lim"#,
+            r#"it=10 flag>{}</Action>"#,
+        ] {
+            finder
+                .push(chunk, |_, event| {
+                    events.push(event);
+                    Ok(())
+                })
+                .unwrap();
+        }
+
+        let tag = match &events[0] {
+            TagEvent::Open(tag) => tag,
+            other => panic!("Expected Open, got {:?}", other),
+        };
+        assert_eq!(tag.name, "Action");
+        assert_eq!(tag.attributes.len(), 3);
+        assert_eq!(tag.attributes["type"], "web search");
+        assert_eq!(tag.attributes["id"], "3");
+        assert_eq!(tag.attributes["limit"], "10");
+    }
+
+    fn collect_events(finder: &mut TagFinder, input: &str) -> Vec<TagEvent> {
+        let mut events = Vec::new();
+        finder
+            .push(input, |_, event| {
+                events.push(event);
+                Ok(())
+            })
+            .unwrap();
+        events
+    }
+
+    fn open_close_trace(events: &[TagEvent]) -> Vec<String> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                TagEvent::Open(tag) => Some(format!("+{}@{}", tag.name, tag.depth)),
+                TagEvent::Close(name, depth) => Some(format!("-{}@{}", name, depth)),
+                TagEvent::Bytes(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_nested_wanted_tags_keep_hierarchy() {
+        let mut finder =
+            TagFinder::new_with_filter(vec!["Outer".to_string(), "Inner".to_string()], vec![]);
+        let events = collect_events(
+            &mut finder,
+            "<Outer><Inner>a</Inner><Inner>b</Inner>tail</Outer> after <Inner>c</Inner>",
+        );
+
+        assert_eq!(
+            open_close_trace(&events),
+            [
+                "+Outer@1", "+Inner@2", "-Inner@2", "+Inner@2", "-Inner@2", "-Outer@1", "+Inner@1",
+                "-Inner@1"
+            ]
+        );
+        // "tail" is still inside Outer after the first </Inner>; " after " is not
+        let text: String = events
+            .iter()
+            .filter_map(|e| match e {
+                TagEvent::Bytes(b) => Some(b.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(text, "abtailc");
+    }
+
+    #[test]
+    fn test_wanted_tag_inside_unwanted_wrapper() {
+        let mut finder = TagFinder::new_with_filter(vec!["Answer".to_string()], vec![]);
+        let events = collect_events(
+            &mut finder,
+            "<div><Answer><x>1</x></Answer></div> trailing <p>prose</p>",
+        );
+
+        assert_eq!(
+            open_close_trace(&events),
+            ["+Answer@1", "+x@2", "-x@2", "-Answer@1"]
+        );
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e, TagEvent::Bytes(text) if text.contains("prose"))));
+    }
+
+    #[test]
+    fn test_wildcard_wanted_tags() {
+        let mut finder =
+            TagFinder::new_with_filter(vec!["tool_*".to_string(), "Step?".to_string()], vec![]);
+        let events = collect_events(
+            &mut finder,
+            "<tool_search>q</tool_search><TOOL_CALC>1+1</TOOL_CALC><tools>no</tools>\
+             <Step1>a</Step1><Step10>no</Step10>",
+        );
+
+        // Open events carry the concrete tag name that matched
+        assert_eq!(
+            open_close_trace(&events),
+            [
+                "+tool_search@1",
+                "-tool_search@1",
+                "+TOOL_CALC@1",
+                "-TOOL_CALC@1",
+                "+Step1@1",
+                "-Step1@1"
+            ]
+        );
+
+        // Globs are literal apart from `*` and `?`
+        let mut finder = TagFinder::new_with_filter(vec!["a.b*".to_string()], vec![]);
+        let events = collect_events(&mut finder, "<axb>no</axb><a.bc>yes</a.bc>");
+        assert_eq!(open_close_trace(&events), ["+a.bc@1", "-a.bc@1"]);
+    }
+
+    #[test]
+    fn test_passthrough_text_outside_wanted_tags() {
+        let mut finder =
+            TagFinder::new_with_filter(vec!["Action".to_string()], vec!["think".to_string()]);
+        finder.set_passthrough(true);
+
+        let mut events = collect_events(&mut finder, "I should search. <think>secret</think>Ok ");
+        events.extend(collect_events(
+            &mut finder,
+            "<Action>go</Action> done <p>see</p> bye",
+        ));
+
+        assert_eq!(
+            finder.take_passthrough(),
+            "I should search. Ok  done see bye"
+        );
+        assert_eq!(finder.take_passthrough(), "");
+        // The wanted payload is unaffected
+        assert_eq!(open_close_trace(&events), ["+Action@1", "-Action@1"]);
+    }
+
+    #[test]
+    fn test_on_text_callback_gets_prose_with_offsets() {
+        use std::sync::{Arc, Mutex};
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let mut finder =
+            TagFinder::new_with_filter(vec!["Action".to_string()], vec!["think".to_string()]);
+        finder.on_text(move |offset, text| sink.lock().unwrap().push((offset, text.to_string())));
+
+        let input = "Plan: <think>secret</think>look <Action>go</Action> done";
+        let mut events = Vec::new();
+        for chunk in input.as_bytes().chunks(5) {
+            events.extend(collect_events(
+                &mut finder,
+                std::str::from_utf8(chunk).unwrap(),
+            ));
+        }
+
+        let seen = seen.lock().unwrap();
+        let prose: String = seen.iter().map(|(_, text)| text.as_str()).collect();
+        assert_eq!(prose, "Plan: look  done");
+        for (offset, text) in seen.iter() {
+            assert_eq!(&input[*offset..*offset + text.len()], text);
+        }
+        // Nothing is buffered without set_passthrough
+        assert_eq!(finder.take_passthrough(), "");
+        assert_eq!(open_close_trace(&events), ["+Action@1", "-Action@1"]);
+    }
+
+    #[test]
+    fn test_unclosed_inner_tags_closed_by_outer() {
+        let mut finder = TagFinder::new();
+        let events = collect_events(&mut finder, "<Person><name>Al</nam><b>x</Person>");
+
+        assert_eq!(
+            open_close_trace(&events),
+            [
+                "+Person@1",
+                "+name@2",
+                "+b@3",
+                "-b@3",
+                "-name@2",
+                "-Person@1"
+            ]
+        );
+        assert!(finder.open_tags.is_empty());
+    }
+
+    #[test]
+    fn test_self_closing_tag() {
+        let mut finder = TagFinder::new();
+        let events = collect_events(&mut finder, r#"<Person><br/><img src="a.png" /></Person>"#);
+
+        assert_eq!(
+            open_close_trace(&events),
+            [
+                "+Person@1",
+                "+br@2",
+                "-br@2",
+                "+img@2",
+                "-img@2",
+                "-Person@1"
+            ]
+        );
+        assert!(matches!(&events[3], TagEvent::Open(tag) if tag.attributes["src"] == "a.png"));
+    }
+
+    #[test]
+    fn test_finish_drops_partial_tag() {
+        let mut finder = TagFinder::new();
+        let mut events = Vec::new();
+        finder
+            .push("<root>text</ro", |_, event| {
+                events.push(event);
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(finder.finish(), "</ro");
+
+        // The finder starts over after finish
+        events.clear();
+        finder
+            .push("plain <next>x</next>", |_, event| {
+                events.push(event);
+                Ok(())
+            })
+            .unwrap();
+        assert!(matches!(&events[0], TagEvent::Open(tag) if tag.name == "next" && tag.depth == 1));
+        assert!(
+            !events
+                .iter()
+                .any(|e| matches!(e, TagEvent::Bytes(text) if text.contains("plain"))),
+            "Text outside a tag after finish should not be emitted"
+        );
+    }
+
+    #[test]
+    fn test_many_tags_in_one_chunk() {
+        // A single large chunk with thousands of tags must be scanned in one
+        // pass; every tag and payload should still be reported in order.
+        let count = 20_000;
+        let mut input = String::from("<items>");
+        for i in 0..count {
+            input.push_str(&format!("<item>{}</item>", i));
+        }
+        input.push_str("</items>");
+
+        let mut finder = TagFinder::new();
+        let mut opens = 0;
+        let mut closes = 0;
+        let mut payload = Vec::new();
+        finder
+            .push(&input, |_, event| {
+                match event {
+                    TagEvent::Open(_) => opens += 1,
+                    TagEvent::Close(_, _) => closes += 1,
+                    TagEvent::Bytes(text) => payload.push(text),
+                }
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(opens, count + 1);
+        assert_eq!(closes, count + 1);
+        assert_eq!(payload.len(), count);
+        assert_eq!(payload[0], "0");
+        assert_eq!(payload[count - 1], (count - 1).to_string());
+    }
+
+    #[test]
+    fn test_one_chunk_scan_time_is_linear() {
+        // Four times the tags should take about four times as long. Scanning
+        // that drained the buffer once per tag took about sixteen times as
+        // long; the bound sits between the two. Take the fastest of a few
+        // runs so a busy machine doesn't fail the test.
+        fn fastest_scan(count: usize) -> std::time::Duration {
+            let mut input = String::from("<items>");
+            for i in 0..count {
+                input.push_str(&format!("<item>{}</item>", i));
+            }
+            input.push_str("</items>");
+            (0..3)
+                .map(|_| {
+                    let mut finder = TagFinder::new();
+                    let start = std::time::Instant::now();
+                    finder.push(&input, |_, _| Ok(())).unwrap();
+                    start.elapsed()
+                })
+                .min()
+                .unwrap()
+        }
+
+        let small = fastest_scan(20_000);
+        let large = fastest_scan(80_000);
+        assert!(
+            large < small * 8,
+            "4x the tags took {:?} against {:?}",
+            large,
+            small
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "unsafe-debug"))]
+    fn test_debug_redacts_buffered_text() {
+        let mut finder = TagFinder::new();
+        finder.set_passthrough(true);
+        collect_events(&mut finder, "password=hunter2 <Answer>secret</Ans");
+
+        let shown = format!("{:?}", finder);
+        assert!(!shown.contains("hunter2"), "{}", shown);
+        assert!(!shown.contains("</Ans"), "{}", shown);
+        assert!(shown.contains("open_tags: [\"Answer\"]"), "{}", shown);
+    }
+
+    #[test]
+    fn test_trace_records_offsets_and_skips() {
+        let mut finder =
+            TagFinder::new_with_filter(vec!["Answer".to_string()], vec!["think".to_string()]);
+        finder.set_trace(true);
+        // Split mid-tag so offsets have to survive the buffer being drained
+        collect_events(&mut finder, "<think>hm</think><Note/><Ans");
+        collect_events(&mut finder, "wer>hi</Answer></Stray>");
+
+        let offsets: Vec<usize> = finder.trace().iter().map(|e| e.offset).collect();
+        assert_eq!(offsets, vec![0, 9, 17, 24, 32, 34, 43]);
+        assert_eq!(
+            format_trace(finder.trace()),
+            concat!(
+                "     0  skip <think> (ignored)\n",
+                "     9  skip <think> (ignored)\n",
+                "    17  skip <Note> (not_wanted)\n",
+                "    24  +Answer@1\n",
+                "    32    \"hi\"\n",
+                "    34  -Answer@1\n",
+                "    43  skip <Stray> (unmatched_close)\n",
+            )
+        );
+        #[cfg(feature = "serde_json")]
+        {
+            let close = finder.trace()[5].to_json();
+            assert_eq!(close["kind"], "close");
+            assert_eq!(close["name"], "Answer");
+            #[cfg(feature = "serde")]
+            assert_eq!(serde_json::to_value(&finder.trace()[5]).unwrap(), close);
+        }
+        finder.set_trace(false);
+        assert!(finder.trace().is_empty());
+    }
+
+    #[test]
+    fn test_pull_events() {
+        let mut finder = TagFinder::new();
+        finder.feed("<A>one</A><B>tw").unwrap();
+        finder.feed("o</B>").unwrap();
+
+        // A consumer that stops after the first element keeps the rest queued
+        let first: Vec<usize> = finder.events().take(2).map(|(offset, _)| offset).collect();
+        assert_eq!(first, vec![0, 3]);
+        assert_eq!(finder.events().len(), 5);
+
+        let rest: Vec<(usize, String)> = finder
+            .events()
+            .map(|(offset, event)| match event {
+                TagEvent::Open(tag) => (offset, format!("+{}", tag.name)),
+                TagEvent::Bytes(text) => (offset, text),
+                TagEvent::Close(name, _) => (offset, format!("-{}", name)),
+            })
+            .collect();
+        assert_eq!(
+            rest,
+            vec![
+                (6, "-A".to_string()),
+                (10, "+B".to_string()),
+                (13, "tw".to_string()),
+                (15, "o".to_string()),
+                (16, "-B".to_string()),
+            ]
+        );
+        assert!(finder.next_event().is_none());
+    }
+
+    #[test]
+    fn test_long_prose_tail_splits_on_char_boundary() {
+        let mut finder = TagFinder::new_with_filter(vec!["Answer".to_string()], vec![]);
+        // 202 bytes, so the last 200 would start inside the first "é"
+        let prose = format!("x{}x", "\u{e9}".repeat(100));
+        finder.push(&prose, |_, _| Ok(())).unwrap();
+
+        let mut text = String::new();
+        finder
+            .push("<Answer>ok</Answer>", |offset, event| {
+                if let TagEvent::Bytes(bytes) = event {
+                    assert_eq!(offset, prose.len() + "<Answer>".len());
+                    text.push_str(&bytes);
+                }
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(text, "ok");
+    }
+
+    #[test]
+    fn test_reset_starts_a_new_stream() {
+        let mut finder =
+            TagFinder::new_with_filter(vec!["Answer".to_string()], vec!["think".to_string()]);
+        finder.feed("<think>x<Answer>half").unwrap();
+        finder.reset();
+        assert_eq!(finder.buffered_len(), 0);
+        assert!(finder.next_event().is_none());
+
+        // Offsets restart, and the filters still apply
+        finder.feed("<think>x</think><Answer>ok</Answer>").unwrap();
+        let opens: Vec<(usize, String)> = finder
+            .events()
+            .filter_map(|(offset, event)| match event {
+                TagEvent::Open(tag) => Some((offset, tag.name)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(opens, vec![(16, "Answer".to_string())]);
+    }
+
+    /// Records span names, their parents and any `bytes` recorded on them.
+    #[cfg(feature = "tracing")]
+    type LoggedSpan = (String, Option<u64>, Option<u64>);
+
+    #[cfg(feature = "tracing")]
+    #[derive(Default)]
+    struct SpanLog {
+        spans: std::sync::Mutex<Vec<LoggedSpan>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for SpanLog {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut spans = self.spans.lock().unwrap();
+            let parent = span.parent().map(|id| id.into_u64());
+            spans.push((span.metadata().name().to_string(), parent, None));
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, id: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            struct Bytes(Option<u64>);
+            impl tracing::field::Visit for Bytes {
+                fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+                    if field.name() == "bytes" {
+                        self.0 = Some(value);
+                    }
+                }
+                fn record_debug(&mut self, _: &tracing::field::Field, _: &dyn fmt::Debug) {}
+            }
+            let mut bytes = Bytes(None);
+            values.record(&mut bytes);
+            self.spans.lock().unwrap()[id.into_u64() as usize - 1].2 = bytes.0;
+        }
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+        fn event(&self, _: &tracing::Event<'_>) {}
+        fn enter(&self, _: &tracing::span::Id) {}
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tag_spans_nest_and_count_bytes() {
+        let log = std::sync::Arc::new(SpanLog::default());
+        tracing::subscriber::with_default(log.clone(), || {
+            let mut finder = TagFinder::new_with_filter(vec!["Answer".to_string()], vec![]);
+            for chunk in ["<Answer><text>he", "llo</text>", "</Answer>"] {
+                finder.push(chunk, |_, _| Ok(())).unwrap();
+            }
+        });
+
+        let spans = log.spans.lock().unwrap().clone();
+        let tags: Vec<_> = spans.iter().filter(|(name, ..)| name == "tag").collect();
+        // <Answer> is span 2 (after the first chunk span); <text> nests under it
+        assert_eq!(tags[0], &("tag".to_string(), None, Some(0)));
+        assert_eq!(tags[1], &("tag".to_string(), Some(2), Some(5)));
+        let chunks = spans.iter().filter(|(name, ..)| name == "tag_finder.push");
+        assert_eq!(chunks.count(), 3);
+    }
+
+    /// A lenient document: mixed quoting and unquoted attributes, close tags
+    /// in a different case or missing, self-closing tags, and CDATA.
+    fn relaxed_document() -> impl Strategy<Value = String> {
+        let text = "[a-zA-Z0-9 .,\u{e9}\n]{0,12}";
+        let attr = (
+            "[a-z]{1,4}",
+            "[a-z0-9 ]{0,5}",
+            prop_oneof![Just('"'), Just('\''), Just(' ')],
+        )
+            .prop_map(|(key, value, quote)| match quote {
+                ' ' => format!(" {}={}", key, value.replace(' ', "_")),
+                q => format!(" {}={}{}{}", key, q, value, q),
+            });
+        let leaf = prop_oneof![
+            text.prop_map(String::from),
+            "[a-z<> &]{0,8}".prop_map(|body| format!("<![CDATA[{}]]>", body)),
+        ];
+        leaf.prop_recursive(4, 32, 4, move |inner| {
+            (
+                prop_oneof![
+                    Just("Answer"),
+                    Just("item"),
+                    Just("tool_call"),
+                    Just("think")
+                ],
+                prop::collection::vec(attr.clone(), 0..3),
+                prop::collection::vec(inner, 0..4),
+                0..4u8,
+            )
+                .prop_map(|(name, attrs, children, close)| {
+                    let attrs = attrs.concat();
+                    let body = children.concat();
+                    match close {
+                        0 => format!("<{}{}>{}</{}>", name, attrs, body, name),
+                        1 => format!("<{}{}>{}</{}>", name, attrs, body, name.to_uppercase()),
+                        2 => format!("<{}{}>{}", name, attrs, body),
+                        _ => format!("<{}{}/>{}", name, attrs, body),
+                    }
+                })
+        })
+    }
+
+    /// Events from pushing `pieces`, with adjacent text merged.
+    fn scan_pieces(wanted: &[&str], pieces: &[&str]) -> Vec<String> {
+        let wanted = wanted.iter().map(|w| w.to_string()).collect();
+        let mut finder = TagFinder::new_with_filter(wanted, vec!["think".to_string()]);
+        let mut seen: Vec<(usize, String, bool)> = Vec::new();
+        for piece in pieces {
+            finder
+                .push(piece, |offset, event| {
+                    match event {
+                        TagEvent::Bytes(text) => match seen.last_mut() {
+                            Some((start, prev, true)) if *start + prev.len() == offset => {
+                                prev.push_str(&text)
+                            }
+                            _ => seen.push((offset, text, true)),
+                        },
+                        TagEvent::Open(tag) => {
+                            let mut attrs: Vec<_> = tag.attributes.into_iter().collect();
+                            attrs.sort();
+                            let open = format!("+{} {} {:?}", tag.name, tag.depth, attrs);
+                            seen.push((offset, open, false));
+                        }
+                        TagEvent::Close(name, depth) => {
+                            seen.push((offset, format!("-{} {}", name, depth), false))
+                        }
+                    }
+                    Ok(())
+                })
+                .unwrap();
+        }
+        seen.into_iter()
+            .map(|(offset, event, _)| format!("{} {}", offset, event))
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_chunking_does_not_change_relaxed_parse(
+            doc in relaxed_document(),
+            prose in "[a-z ]{0,8}",
+            cuts in prop::collection::vec(any::<prop::sample::Index>(), 0..8),
+            only_answer in any::<bool>(),
+        ) {
+            let input = format!("{}{}{}", prose, doc, prose);
+            let mut cuts: Vec<usize> = cuts
+                .iter()
+                .map(|cut| cut.index(input.len() + 1))
+                .filter(|&cut| input.is_char_boundary(cut))
+                .collect();
+            cuts.sort_unstable();
+            cuts.dedup();
+            let mut pieces = Vec::new();
+            let mut start = 0;
+            for cut in cuts.into_iter().chain(std::iter::once(input.len())) {
+                pieces.push(&input[start..cut]);
+                start = cut;
+            }
+
+            let wanted: &[&str] = if only_answer { &["answer"] } else { &[] };
+            prop_assert_eq!(scan_pieces(wanted, &[&input]), scan_pieces(wanted, &pieces));
+        }
+    }
+
+    #[test]
+    fn test_builder_matches_setters() {
+        let input = "pre<think>hm</think><Answer>42</Answer>post";
+        let mut built = TagFinder::builder()
+            .wanted(["Answer"])
+            .ignored(["think"])
+            .passthrough(true)
+            .trace(true)
+            .build();
+        let mut set = TagFinder::new_with_filter(vec!["Answer".into()], vec!["think".into()]);
+        set.set_passthrough(true);
+        set.set_trace(true);
+        built.feed(input).unwrap();
+        set.feed(input).unwrap();
+        assert_eq!(
+            format!("{:?}", built.events().collect::<Vec<_>>()),
+            format!("{:?}", set.events().collect::<Vec<_>>())
+        );
+        assert_eq!(built.take_passthrough(), set.take_passthrough());
+        assert_eq!(built.trace().len(), set.trace().len());
+        assert!(!built.trace().is_empty());
+    }
+
+    #[test]
+    fn test_builder_wanted_regex() {
+        let mut finder = TagFinder::builder()
+            .wanted(["Answer"])
+            .wanted_regex(Regex::new(r"^tool_(search|calc)$").unwrap())
+            .build();
+        let events = collect_events(
+            &mut finder,
+            "<tool_search>q</tool_search><tool_other>no</tool_other>\
+             <TOOL_CALC>no</TOOL_CALC><tool_calc>1</tool_calc><Answer>2</Answer>",
+        );
+        // Regexes see the name as written, so case matters unless `(?i)` is set
+        assert_eq!(
+            open_close_trace(&events),
+            [
+                "+tool_search@1",
+                "-tool_search@1",
+                "+tool_calc@1",
+                "-tool_calc@1",
+                "+Answer@1",
+                "-Answer@1"
+            ]
+        );
+
+        // A regex on its own restricts the wanted tags
+        let mut finder = TagFinder::builder()
+            .wanted_regex(Regex::new("(?i)^step[0-9]+$").unwrap())
+            .build();
+        let events = collect_events(&mut finder, "<Step12>a</Step12><note>b</note>");
+        assert_eq!(open_close_trace(&events), ["+Step12@1", "-Step12@1"]);
+    }
 }
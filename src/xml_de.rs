@@ -0,0 +1,507 @@
+use crate::xml_types::{XmlError, XmlValue};
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeSeed, EnumAccess, IntoDeserializer, VariantAccess, Visitor};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::fmt;
+
+/// Fill any `Deserialize` type from a tree built by `StreamParser`.
+///
+/// Fields match child elements and attributes by name, ignoring case, and a
+/// struct wrapped in a tag of its own name (`<item><Employee>...`) is
+/// unwrapped. A list is either the child elements of one tag
+/// (`<tags><item>a</item>...</tags>`) or a tag repeated under its parent.
+/// Maps key each child by its `key` attribute, or else its tag name. An enum
+/// variant is named by a child tag, the element's own tag, or its text.
+/// Scalars are parsed from the text, and an empty element is `None`.
+pub fn from_value<'de, T: Deserialize<'de>>(value: &'de XmlValue) -> Result<T, XmlError> {
+    T::deserialize(value)
+}
+
+impl de::Error for XmlError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        XmlError::ParserError(msg.to_string())
+    }
+}
+
+/// A value a field can come from.
+enum Node<'de> {
+    Value(&'de XmlValue),
+    Attr(&'de str),
+    /// A tag that appeared more than once under the same parent.
+    Repeated(Vec<&'de XmlValue>),
+}
+
+fn elements(value: &XmlValue) -> impl Iterator<Item = &XmlValue> {
+    value
+        .children()
+        .iter()
+        .filter(|child| child.name().is_some())
+}
+
+fn attributes(value: &XmlValue) -> impl Iterator<Item = (&str, &str)> {
+    match value {
+        XmlValue::Element(_, attrs, _) => Some(attrs),
+        XmlValue::Text(_) => None,
+    }
+    .into_iter()
+    .flatten()
+    .map(|(key, value)| (key.as_str(), value.as_str()))
+}
+
+impl<'de> Node<'de> {
+    /// The node as a leaf whose text is the scalar.
+    fn scalar(&self) -> Result<Cow<'de, XmlValue>, XmlError> {
+        match self {
+            Node::Value(value) => Ok(Cow::Borrowed(*value)),
+            Node::Attr(text) => Ok(Cow::Owned(XmlValue::Text(text.to_string()))),
+            Node::Repeated(values) => Err(XmlError::ParserError(format!(
+                "<{}> appears {} times where one value was expected",
+                values[0].name().unwrap_or_default(),
+                values.len()
+            ))),
+        }
+    }
+
+    fn parse<T>(&self) -> Result<T, XmlError>
+    where
+        T: std::str::FromStr,
+        T::Err: fmt::Display,
+    {
+        self.scalar()?.parse()
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Node::Value(XmlValue::Text(text)) => text.trim().is_empty(),
+            Node::Value(value) => value.children().is_empty() && attributes(value).next().is_none(),
+            Node::Attr(_) | Node::Repeated(_) => false,
+        }
+    }
+
+    fn element(&self, expected: &str) -> Result<&'de XmlValue, XmlError> {
+        match self {
+            Node::Value(value @ XmlValue::Element(..)) => Ok(value),
+            _ => Err(XmlError::ParserError(format!(
+                "expected {}, found text {:?}",
+                expected,
+                self.scalar()?.text()
+            ))),
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, XmlError> for Node<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! forward_to_node {
+    ($($method:ident($($arg:ident: $ty:ty),*),)*) => {$(
+        fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, XmlError> {
+            Node::Value(self).$method($($arg,)* visitor)
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for &'de XmlValue {
+    type Error = XmlError;
+
+    forward_to_node! {
+        deserialize_any(), deserialize_bool(), deserialize_i8(), deserialize_i16(),
+        deserialize_i32(), deserialize_i64(), deserialize_i128(), deserialize_u8(),
+        deserialize_u16(), deserialize_u32(), deserialize_u64(), deserialize_u128(),
+        deserialize_f32(), deserialize_f64(), deserialize_char(), deserialize_str(),
+        deserialize_string(), deserialize_bytes(), deserialize_byte_buf(),
+        deserialize_option(), deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(), deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(), deserialize_ignored_any(),
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, XmlError> {
+            visitor.$visit(self.parse()?)
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for Node<'de> {
+    type Error = XmlError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, XmlError> {
+        match &self {
+            Node::Repeated(_) => self.deserialize_seq(visitor),
+            Node::Value(value)
+                if elements(value).next().is_some() || attributes(value).next().is_some() =>
+            {
+                self.deserialize_map(visitor)
+            }
+            _ => visitor.visit_string(self.scalar()?.text()),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, XmlError> {
+        let value = self.scalar()?;
+        visitor.visit_bool(value.as_bool().map_or_else(|| value.parse(), Ok)?)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, XmlError> {
+        visitor.visit_string(self.scalar()?.text())
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, XmlError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, XmlError> {
+        visitor.visit_byte_buf(self.scalar()?.text().into_bytes())
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, XmlError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, XmlError> {
+        if self.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, XmlError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, XmlError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, XmlError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, XmlError> {
+        let items: Vec<Node<'de>> = match self {
+            Node::Repeated(values) => values.into_iter().map(Node::Value).collect(),
+            Node::Value(value) if elements(value).next().is_some() => {
+                elements(value).map(Node::Value).collect()
+            }
+            // A single occurrence of a tag that could have repeated
+            node if node.is_empty() => Vec::new(),
+            node => vec![node],
+        };
+        let mut seq = SeqDeserializer::new(items.into_iter());
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, XmlError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, XmlError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, XmlError> {
+        let element = self.element("a map")?;
+        let entries = attributes(element)
+            .map(|(key, value)| (key, Node::Attr(value)))
+            .chain(elements(element).map(|child| {
+                let key = child.attr("key").or(child.name()).unwrap_or_default();
+                (key, Node::Value(child))
+            }));
+        let mut map = MapDeserializer::new(entries);
+        let value = visitor.visit_map(&mut map)?;
+        map.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, XmlError> {
+        let mut element = self.element(name)?;
+        let mut children = elements(element);
+        if let (Some(only), None) = (children.next(), children.next()) {
+            let wrapped = |tag: &str| tag.eq_ignore_ascii_case(name);
+            if !element.name().is_some_and(wrapped) && only.name().is_some_and(wrapped) {
+                element = only;
+            }
+        }
+        let entries = fields.iter().filter_map(|&field| {
+            if let Some((_, value)) =
+                attributes(element).find(|(key, _)| key.eq_ignore_ascii_case(field))
+            {
+                return Some((field, Node::Attr(value)));
+            }
+            let mut matching: Vec<_> = elements(element)
+                .filter(|child| {
+                    child
+                        .name()
+                        .is_some_and(|tag| tag.eq_ignore_ascii_case(field))
+                })
+                .collect();
+            match matching.len() {
+                0 => None,
+                1 => Some((field, Node::Value(matching.remove(0)))),
+                _ => Some((field, Node::Repeated(matching))),
+            }
+        });
+        let mut map = MapDeserializer::new(entries);
+        let value = visitor.visit_map(&mut map)?;
+        map.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, XmlError> {
+        let variant_named = |tag: Option<&str>| {
+            let tag = tag?;
+            variants
+                .iter()
+                .copied()
+                .find(|v| v.eq_ignore_ascii_case(tag))
+        };
+        if let Node::Value(value) = &self {
+            let mut children = elements(value);
+            if let (Some(only), None) = (children.next(), children.next()) {
+                if let Some(variant) = variant_named(only.name()) {
+                    return visitor
+                        .visit_enum(Variant(Cow::Borrowed(variant), Some(Node::Value(only))));
+                }
+            }
+            if let Some(variant) = variant_named(value.name()) {
+                return visitor.visit_enum(Variant(Cow::Borrowed(variant), Some(self)));
+            }
+        }
+        let text = self.scalar()?.text();
+        let variant = variant_named(Some(&text)).map_or(Cow::Owned(text), Cow::Borrowed);
+        visitor.visit_enum(Variant(variant, None))
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, XmlError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, XmlError> {
+        visitor.visit_unit()
+    }
+}
+
+/// An enum variant's name, and its content unless it came from text.
+struct Variant<'de>(Cow<'static, str>, Option<Node<'de>>);
+
+impl<'de> EnumAccess<'de> for Variant<'de> {
+    type Error = XmlError;
+    type Variant = Self;
+
+    fn variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<(S::Value, Self), XmlError> {
+        let name: de::value::StrDeserializer<XmlError> = self.0.as_ref().into_deserializer();
+        Ok((seed.deserialize(name)?, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Variant<'de> {
+    type Error = XmlError;
+
+    fn unit_variant(self) -> Result<(), XmlError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<S::Value, XmlError> {
+        seed.deserialize(self.content()?)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, XmlError> {
+        de::Deserializer::deserialize_seq(self.content()?, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, XmlError> {
+        de::Deserializer::deserialize_struct(self.content()?, "", fields, visitor)
+    }
+}
+
+impl<'de> Variant<'de> {
+    fn content(self) -> Result<Node<'de>, XmlError> {
+        self.1.ok_or_else(|| {
+            XmlError::ParserError(format!(
+                "variant {} needs a <{}> tag with its content",
+                self.0, self.0
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::StreamParser;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Role {
+        Admin,
+        Guest,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Cat {
+        lives: u8,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Pet {
+        Cat(Cat),
+        Rock,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Employee {
+        name: String,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Person {
+        id: u32,
+        name: String,
+        age: Option<u8>,
+        nick: Option<String>,
+        active: bool,
+        tags: Vec<String>,
+        score: Vec<f64>,
+        settings: HashMap<String, i64>,
+        role: Role,
+        pet: Pet,
+        friends: Vec<Employee>,
+    }
+
+    fn parse(document: &str) -> XmlValue {
+        let mut parser = StreamParser::new();
+        let (head, tail) = document.split_at(document.len() / 2);
+        assert_eq!(parser.step(head), Ok(None));
+        parser.step(tail).unwrap().expect("root closed")
+    }
+
+    #[test]
+    fn test_from_value_fills_struct() {
+        let value = parse(concat!(
+            "<Person id=\"7\"><Name>Ada</Name><age>36</age><nick></nick>",
+            "<active>yes</active><tags><item>a</item><item>b</item></tags>",
+            "<score>1.5</score><score>2</score>",
+            "<settings><item key=\"width\">80</item><item key=\"depth\">3</item></settings>",
+            "<role>admin</role><pet><Cat><lives>9</lives></Cat></pet>",
+            "<friends><item><Employee><name>Bob</name></Employee></item>",
+            "<item><name>Cy</name></item></friends><extra>ignored</extra></Person>",
+        ));
+        let person: Person = from_value(&value).unwrap();
+        let mut settings = HashMap::new();
+        settings.insert("width".to_string(), 80);
+        settings.insert("depth".to_string(), 3);
+        assert_eq!(
+            person,
+            Person {
+                id: 7,
+                name: "Ada".into(),
+                age: Some(36),
+                nick: None,
+                active: true,
+                tags: vec!["a".into(), "b".into()],
+                score: vec![1.5, 2.0],
+                settings,
+                role: Role::Admin,
+                pet: Pet::Cat(Cat { lives: 9 }),
+                friends: vec![
+                    Employee { name: "Bob".into() },
+                    Employee { name: "Cy".into() }
+                ],
+            }
+        );
+
+        // A tag that could repeat but appears once is a one-item list
+        assert_eq!(
+            from_value::<Vec<f64>>(&parse("<score>4</score>")),
+            Ok(vec![4.0])
+        );
+        assert_eq!(from_value::<Pet>(&parse("<pet>rock</pet>")), Ok(Pet::Rock));
+        assert_eq!(from_value::<Pet>(&parse("<Rock/>")), Ok(Pet::Rock));
+    }
+
+    #[test]
+    fn test_from_value_errors() {
+        let err = from_value::<Cat>(&parse("<Cat><lives>many</lives></Cat>")).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("<lives> text \"many\" is not a valid u8"),
+            "{}",
+            err
+        );
+        let err = from_value::<Cat>(&parse("<Cat><legs>4</legs></Cat>")).unwrap_err();
+        assert!(err.to_string().contains("missing field `lives`"), "{}", err);
+        let err =
+            from_value::<Cat>(&parse("<Cat><lives>1</lives><lives>2</lives></Cat>")).unwrap_err();
+        assert!(
+            err.to_string().contains("<lives> appears 2 times"),
+            "{}",
+            err
+        );
+        assert!(from_value::<Role>(&parse("<role>owner</role>")).is_err());
+    }
+}
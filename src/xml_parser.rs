@@ -3,15 +3,22 @@ use std::collections::HashMap;
 use std::fmt;
 use xml::{Event, Parser};
 
+/// Builds an `XmlValue` tree from a document that arrives in chunks.
 pub struct StreamParser {
     parser: Parser,
+    events: Vec<Event>, // events of the root element so far
+    depth: usize,       // elements open right now
     done: bool,
+    root: Option<XmlValue>, // the root once it closed, for `finish`
 }
 
 impl fmt::Debug for StreamParser {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("StreamParser")
+            .field("events", &self.events.len())
+            .field("depth", &self.depth)
             .field("done", &self.done)
+            .field("root", &self.root.is_some())
             .finish()
     }
 }
@@ -20,7 +27,10 @@ impl Default for StreamParser {
     fn default() -> Self {
         Self {
             parser: Parser::new(),
+            events: Vec::new(),
+            depth: 0,
             done: false,
+            root: None,
         }
     }
 }
@@ -34,20 +44,52 @@ impl StreamParser {
         self.done
     }
 
-    pub fn step(&mut self, chunk: &str) -> Result<Vec<Event>, XmlError> {
+    /// Feed the next chunk. Returns the root element once its closing tag
+    /// has arrived; input after that is ignored.
+    pub fn step(&mut self, chunk: &str) -> Result<Option<XmlValue>, XmlError> {
+        if self.done {
+            return Ok(None);
+        }
         self.parser.feed_str(chunk);
-        let events: Result<Vec<Event>, XmlError> = self
-            .parser
-            .by_ref()
-            .map(|e| e.map_err(|e| XmlError::ParserError(e.to_string())))
-            .collect();
+        for event in self.parser.by_ref() {
+            let event = event.map_err(|e| XmlError::ParserError(e.to_string()))?;
+            match event {
+                Event::ElementStart(_) => self.depth += 1,
+                Event::ElementEnd(_) => self.depth = self.depth.saturating_sub(1),
+                _ => {}
+            }
+            let closes_root = matches!(event, Event::ElementEnd(_)) && self.depth == 0;
+            self.events.push(event);
+            if closes_root {
+                self.done = true;
+                let root = self.build()?;
+                self.root = Some(root.clone());
+                return Ok(Some(root));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Signal the end of input. Returns the root element, also when `step`
+    /// already returned it, or an error if it never started or is still open.
+    pub fn finish(&mut self) -> Result<XmlValue, XmlError> {
+        self.done = true;
+        match &self.root {
+            Some(root) => Ok(root.clone()),
+            None => self.build(),
+        }
+    }
 
-        events
+    fn build(&mut self) -> Result<XmlValue, XmlError> {
+        let events = std::mem::take(&mut self.events);
+        events_to_xml_value(events.into_iter().map(Ok).collect())
     }
 }
 
 /// Convert a vector of XML events to an XmlValue tree
-pub fn events_to_xml_value(events: Vec<Result<Event, XmlError>>) -> Result<XmlValue, XmlError> {
+pub(crate) fn events_to_xml_value(
+    events: Vec<Result<Event, XmlError>>,
+) -> Result<XmlValue, XmlError> {
     let mut stack: Vec<(String, HashMap<String, String>, Vec<XmlValue>)> = Vec::new();
     let mut current_text = String::new();
 
@@ -121,3 +163,40 @@ pub fn events_to_xml_value(events: Vec<Result<Event, XmlError>>) -> Result<XmlVa
 
     Err(XmlError::ParserError("No root element found".to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_returns_root_once_closed() {
+        let mut parser = StreamParser::new();
+        assert_eq!(parser.step("<Person><name>Ada</na").unwrap(), None);
+        let value = parser
+            .step("me><age>36</age></Person><ignored/>")
+            .unwrap()
+            .expect("root closed");
+        assert!(parser.is_done());
+        assert_eq!(value.name(), Some("Person"));
+        assert_eq!(value.get::<i64>("age").unwrap(), 36);
+        assert_eq!(parser.step("<more/>").unwrap(), None);
+    }
+
+    #[test]
+    fn test_finish_after_step_returns_root() {
+        let mut parser = StreamParser::new();
+        let stepped = parser.step("<Person><age>36</age></Person>").unwrap();
+        let finished = parser.finish().unwrap();
+        assert_eq!(stepped, Some(finished.clone()));
+        assert_eq!(finished.get::<i64>("age").unwrap(), 36);
+        assert_eq!(parser.finish().unwrap(), finished);
+    }
+
+    #[test]
+    fn test_finish_errors_on_open_root() {
+        let mut parser = StreamParser::new();
+        parser.step("<Person><name>Ada").unwrap();
+        assert!(parser.finish().is_err());
+        assert!(StreamParser::new().finish().is_err());
+    }
+}
@@ -9,6 +9,173 @@ pub enum XmlValue {
     Text(String),
 }
 
+/// Callbacks for `XmlValue::visit`. `path` names the element, dotted from
+/// the root, e.g. `Person.scores.item[1]`; an index is added when siblings
+/// share a name. Every method defaults to doing nothing.
+pub trait XmlVisitor {
+    /// Called before an element's children. Return `false` to skip them
+    /// (and the matching `exit`).
+    fn enter(&mut self, _path: &str, _element: &XmlValue) -> bool {
+        true
+    }
+
+    /// Called after an element's children.
+    fn exit(&mut self, _path: &str, _element: &XmlValue) {}
+
+    /// Called for each text node, with the path of the element holding it.
+    fn text(&mut self, _path: &str, _text: &str) {}
+}
+
+impl XmlValue {
+    /// Walk the tree depth first, calling `visitor` on every node.
+    pub fn visit(&self, visitor: &mut impl XmlVisitor) {
+        let path = self.name().unwrap_or_default().to_string();
+        self.visit_at(&path, visitor);
+    }
+
+    fn visit_at(&self, path: &str, visitor: &mut impl XmlVisitor) {
+        let children = match self {
+            XmlValue::Text(text) => return visitor.text(path, text),
+            XmlValue::Element(_, _, children) => children,
+        };
+        if !visitor.enter(path, self) {
+            return;
+        }
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for name in children.iter().filter_map(XmlValue::name) {
+            *counts.entry(name).or_default() += 1;
+        }
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        for child in children {
+            let name = match child.name() {
+                Some(name) => name,
+                None => {
+                    child.visit_at(path, visitor);
+                    continue;
+                }
+            };
+            let index = seen.entry(name).or_default();
+            let child_path = if counts[name] > 1 {
+                format!("{}.{}[{}]", path, name, index)
+            } else {
+                format!("{}.{}", path, name)
+            };
+            *index += 1;
+            child.visit_at(&child_path, visitor);
+        }
+        visitor.exit(path, self);
+    }
+
+    /// Tag name of an element; `None` for text.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            XmlValue::Element(name, _, _) => Some(name),
+            XmlValue::Text(_) => None,
+        }
+    }
+
+    pub fn attr(&self, key: &str) -> Option<&str> {
+        match self {
+            XmlValue::Element(_, attrs, _) => attrs.get(key).map(String::as_str),
+            XmlValue::Text(_) => None,
+        }
+    }
+
+    /// Child nodes of an element; empty for text.
+    pub fn children(&self) -> &[XmlValue] {
+        match self {
+            XmlValue::Element(_, _, children) => children,
+            XmlValue::Text(_) => &[],
+        }
+    }
+
+    /// First child element named `name`, compared case-insensitively like tags.
+    pub fn child(&self, name: &str) -> Option<&XmlValue> {
+        self.children()
+            .iter()
+            .find(|child| child.name().is_some_and(|n| n.eq_ignore_ascii_case(name)))
+    }
+
+    /// All text inside this node, concatenated and trimmed.
+    pub fn text(&self) -> String {
+        fn collect(value: &XmlValue, out: &mut String) {
+            match value {
+                XmlValue::Text(text) => out.push_str(text),
+                XmlValue::Element(_, _, children) => {
+                    children.iter().for_each(|child| collect(child, out))
+                }
+            }
+        }
+        let mut out = String::new();
+        collect(self, &mut out);
+        out.trim().to_string()
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.text().parse().ok()
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.text().parse().ok()
+    }
+
+    /// `true`/`yes`/`1` or `false`/`no`/`0`, in any case.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.text().to_lowercase().as_str() {
+            "true" | "yes" | "1" => Some(true),
+            "false" | "no" | "0" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Parse this node's text as `T`.
+    pub fn parse<T>(&self) -> Result<T, XmlError>
+    where
+        T: std::str::FromStr,
+        T::Err: fmt::Display,
+    {
+        let text = self.text();
+        text.parse().map_err(|err| {
+            XmlError::ParserError(format!(
+                "<{}> text {:?} is not a valid {}: {}",
+                self.name().unwrap_or("text"),
+                text,
+                std::any::type_name::<T>(),
+                err
+            ))
+        })
+    }
+
+    /// Parse the text of the child element `name` as `T`.
+    pub fn get<T>(&self, name: &str) -> Result<T, XmlError>
+    where
+        T: std::str::FromStr,
+        T::Err: fmt::Display,
+    {
+        match self.child(name) {
+            Some(child) => child.parse(),
+            None => Err(XmlError::ParserError(format!(
+                "<{}> has no <{}> child",
+                self.name().unwrap_or("text"),
+                name
+            ))),
+        }
+    }
+
+    /// Parse the text of every child element as `T`, e.g. the `<item>`s of a list.
+    pub fn parse_vec<T>(&self) -> Result<Vec<T>, XmlError>
+    where
+        T: std::str::FromStr,
+        T::Err: fmt::Display,
+    {
+        self.children()
+            .iter()
+            .filter(|child| child.name().is_some())
+            .map(XmlValue::parse)
+            .collect()
+    }
+}
+
 impl fmt::Display for XmlValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -32,6 +199,122 @@ impl fmt::Display for XmlValue {
     }
 }
 
+/// Text serializes as a string; an element as
+/// `{"name": ..., "attributes": {...}, "children": [...]}`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for XmlValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        match self {
+            XmlValue::Element(name, attrs, children) => {
+                let mut map = serializer.serialize_map(Some(3))?;
+                map.serialize_entry("name", name)?;
+                map.serialize_entry("attributes", attrs)?;
+                map.serialize_entry("children", children)?;
+                map.end()
+            }
+            XmlValue::Text(text) => serializer.serialize_str(text),
+        }
+    }
+}
+
+/// Same shape as the `Serialize` impl.
+impl ToPyObject for XmlValue {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        match self {
+            XmlValue::Element(name, attrs, children) => {
+                let dict = pyo3::types::PyDict::new(py);
+                let children: Vec<PyObject> = children.iter().map(|c| c.to_object(py)).collect();
+                // Setting str keys on a fresh dict can't fail
+                let _ = dict.set_item("name", name);
+                let _ = dict.set_item("attributes", attrs);
+                let _ = dict.set_item("children", children);
+                dict.to_object(py)
+            }
+            XmlValue::Text(text) => text.to_object(py),
+        }
+    }
+}
+
+/// Same shape as the `Serialize` impl.
+#[cfg(feature = "serde_json")]
+impl From<&XmlValue> for serde_json::Value {
+    fn from(value: &XmlValue) -> Self {
+        match value {
+            XmlValue::Element(name, attrs, children) => serde_json::json!({
+                "name": name,
+                "attributes": attrs,
+                "children": children.iter().map(serde_json::Value::from).collect::<Vec<_>>(),
+            }),
+            XmlValue::Text(text) => serde_json::Value::String(text.clone()),
+        }
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl From<XmlValue> for serde_json::Value {
+    fn from(value: XmlValue) -> Self {
+        Self::from(&value)
+    }
+}
+
+/// Strings, numbers and booleans become text; objects need a string `name`
+/// and may have `attributes` (string values) and `children`.
+#[cfg(feature = "serde_json")]
+impl TryFrom<serde_json::Value> for XmlValue {
+    type Error = XmlError;
+
+    fn try_from(value: serde_json::Value) -> Result<Self, XmlError> {
+        use serde_json::Value;
+        let invalid = XmlError::ParserError;
+        match value {
+            Value::String(text) => Ok(XmlValue::Text(text)),
+            Value::Number(number) => Ok(XmlValue::Text(number.to_string())),
+            Value::Bool(flag) => Ok(XmlValue::Text(flag.to_string())),
+            Value::Object(mut object) => {
+                let name = match object.remove("name") {
+                    Some(Value::String(name)) => name,
+                    _ => return Err(invalid("Element object has no string \"name\"".to_string())),
+                };
+                let attrs = match object.remove("attributes") {
+                    None | Some(Value::Null) => HashMap::new(),
+                    Some(Value::Object(attrs)) => attrs
+                        .into_iter()
+                        .map(|(key, value)| match value {
+                            Value::String(value) => Ok((key, value)),
+                            other => Err(invalid(format!(
+                                "Attribute {:?} of <{}> is {}; attributes must be strings",
+                                key, name, other
+                            ))),
+                        })
+                        .collect::<Result<_, _>>()?,
+                    Some(other) => {
+                        return Err(invalid(format!(
+                            "Attributes of <{}> must be an object, got {}",
+                            name, other
+                        )))
+                    }
+                };
+                let children = match object.remove("children") {
+                    None | Some(Value::Null) => Vec::new(),
+                    Some(Value::Array(children)) => children
+                        .into_iter()
+                        .map(XmlValue::try_from)
+                        .collect::<Result<_, _>>()?,
+                    Some(other) => {
+                        return Err(invalid(format!(
+                            "Children of <{}> must be an array, got {}",
+                            name, other
+                        )))
+                    }
+                };
+                Ok(XmlValue::Element(name, attrs, children))
+            }
+            other => Err(invalid(format!("Can't convert {} to XML", other))),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum XmlError {
     UnexpectedEof,
@@ -47,8 +330,163 @@ impl fmt::Display for XmlError {
     }
 }
 
+impl std::error::Error for XmlError {}
+
 impl From<XmlError> for PyErr {
     fn from(err: XmlError) -> PyErr {
         PyValueError::new_err(err.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::StreamParser;
+
+    // Parsed the way callers get one, split mid-tag and mid-text.
+    fn person() -> XmlValue {
+        let mut parser = StreamParser::new();
+        for chunk in [
+            "<Person id=\"7\"><age> 4",
+            "2 </age><active>Yes</act",
+            "ive><scores><item>1.5</item>\n<item>2</item></scores>",
+        ] {
+            assert_eq!(parser.step(chunk), Ok(None));
+        }
+        parser.step("</Person>").unwrap().expect("root closed")
+    }
+
+    #[test]
+    fn test_typed_accessors() {
+        let person = person();
+        assert_eq!(person.name(), Some("Person"));
+        assert_eq!(person.children().len(), 3);
+        assert_eq!(person.attr("id"), Some("7"));
+        assert_eq!(person.child("AGE").and_then(XmlValue::as_i64), Some(42));
+        assert_eq!(
+            person.child("active").and_then(XmlValue::as_bool),
+            Some(true)
+        );
+        assert_eq!(person.get::<u8>("age"), Ok(42));
+        assert_eq!(
+            person.child("scores").unwrap().parse_vec::<f64>(),
+            Ok(vec![1.5, 2.0])
+        );
+    }
+
+    #[test]
+    fn test_typed_accessor_errors() {
+        let person = person();
+        assert_eq!(
+            person.get::<i64>("height"),
+            Err(XmlError::ParserError(
+                "<Person> has no <height> child".to_string()
+            ))
+        );
+        let err = person.get::<i64>("active").unwrap_err().to_string();
+        assert!(
+            err.contains("<active> text \"Yes\" is not a valid i64"),
+            "{}",
+            err
+        );
+        assert_eq!(person.child("active").unwrap().as_f64(), None);
+    }
+
+    #[test]
+    fn test_visit_paths_and_skipping() {
+        #[derive(Default)]
+        struct Record {
+            log: Vec<String>,
+        }
+        impl XmlVisitor for Record {
+            fn enter(&mut self, path: &str, element: &XmlValue) -> bool {
+                self.log.push(format!("+{}", path));
+                element.name() != Some("active")
+            }
+            fn exit(&mut self, path: &str, _: &XmlValue) {
+                self.log.push(format!("-{}", path));
+            }
+            fn text(&mut self, path: &str, text: &str) {
+                self.log.push(format!("{} {:?}", path, text));
+            }
+        }
+
+        let mut record = Record::default();
+        person().visit(&mut record);
+        assert_eq!(
+            record.log,
+            vec![
+                "+Person",
+                "+Person.age",
+                "Person.age \"42\"",
+                "-Person.age",
+                // enter returned false: no text and no exit
+                "+Person.active",
+                "+Person.scores",
+                "+Person.scores.item[0]",
+                "Person.scores.item[0] \"1.5\"",
+                "-Person.scores.item[0]",
+                "+Person.scores.item[1]",
+                "Person.scores.item[1] \"2\"",
+                "-Person.scores.item[1]",
+                "-Person.scores",
+                "-Person",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_visit_streamed_leaves() {
+        // Only text callbacks, as a redaction pass would use.
+        struct Leaves(Vec<String>);
+        impl XmlVisitor for Leaves {
+            fn text(&mut self, path: &str, _: &str) {
+                self.0.push(path.to_string());
+            }
+        }
+
+        let mut parser = StreamParser::new();
+        parser
+            .step("<Batch><item><tag>a</tag><tag>b</tag></item><it")
+            .unwrap();
+        let batch = parser
+            .step("em><tag>c</tag></item><note/></Batch>")
+            .unwrap()
+            .expect("root closed");
+        let mut leaves = Leaves(Vec::new());
+        batch.visit(&mut leaves);
+        assert_eq!(
+            leaves.0,
+            vec![
+                "Batch.item[0].tag[0]",
+                "Batch.item[0].tag[1]",
+                "Batch.item[1].tag",
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_json_round_trip() {
+        let person = person();
+        let json = serde_json::Value::from(&person);
+        assert_eq!(json["attributes"]["id"], "7");
+        assert_eq!(json["children"][0]["children"][0], "42");
+        #[cfg(feature = "serde")]
+        assert_eq!(serde_json::to_value(&person).unwrap(), json);
+        assert_eq!(XmlValue::try_from(json), Ok(person));
+
+        let typed = serde_json::json!({"name": "n", "children": [1, true]});
+        assert_eq!(
+            XmlValue::try_from(typed).unwrap().children(),
+            &[XmlValue::Text("1".into()), XmlValue::Text("true".into())]
+        );
+        assert_eq!(
+            XmlValue::try_from(serde_json::json!({"name": "n", "attributes": {"a": 1}})),
+            Err(XmlError::ParserError(
+                "Attribute \"a\" of <n> is 1; attributes must be strings".to_string()
+            ))
+        );
+        assert!(XmlValue::try_from(serde_json::json!([1])).is_err());
+    }
+}